@@ -0,0 +1,109 @@
+//! Named profiles from `config.toml`, a profile fills in the generate options that weren't passed
+//! on the command line
+//!
+//! ```toml
+//! [profiles.resale-5seat]
+//! edition = "extreme"
+//! seats = 5
+//! expiry = "1y"
+//! maintenance = "max"
+//! format = "csv"
+//! verify_after_generate = true
+//! ```
+
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+use aida64_keys_lib::cli::{self, Length};
+use aida64_keys_lib::KeyEdition;
+use serde::{Deserialize, Deserializer};
+
+use crate::OutputFormat;
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub edition: Option<KeyEdition>,
+    pub seats: Option<i32>,
+    /// Same format as `--expiry`
+    #[serde(default, deserialize_with = "length")]
+    pub expiry: Option<Length>,
+    /// Same format as `--maintenance`
+    #[serde(default, deserialize_with = "length")]
+    pub maintenance: Option<Length>,
+    pub format: Option<OutputFormat>,
+    /// Same as `--verify-after-generate`, the flag turns it on even when this is false
+    pub verify_after_generate: Option<bool>,
+}
+
+/// `$XDG_CONFIG_HOME/aida64-keys/config.toml`, falling back to `~/.config` and to `%APPDATA%` on
+/// Windows
+pub fn default_path() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+
+    Some(config_dir.join("aida64-keys").join("config.toml"))
+}
+
+fn length<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Length>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    cli::parse_length(&value).map(Some).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let config: Config = toml::from_str(
+            r#"
+            [profiles.trial]
+            edition = "engineer"
+            expiry = "30d"
+            maintenance = "2030-01-01"
+            "#,
+        )
+        .unwrap();
+
+        let profile = &config.profiles["trial"];
+        assert_eq!(profile.edition, Some(KeyEdition::Engineer));
+        assert_eq!(profile.seats, None);
+        assert!(matches!(profile.expiry, Some(Length::Duration(days)) if days.num_days() == 30));
+        assert!(matches!(profile.maintenance, Some(Length::Until(_))));
+        assert!(!config.profiles.contains_key("resale"));
+
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.profiles.is_empty());
+    }
+
+    #[test]
+    fn invalid() {
+        let parse = |text: &str| toml::from_str::<Config>(text).err().unwrap().to_string();
+
+        let e = parse("[profiles.trial]\nexpiry = \"soon\"\n");
+        assert!(e.contains("not 'soon'"), "{e}");
+        let e = parse("[profiles.trial]\nmaintenance = \"10x\"\n");
+        assert!(e.contains("not '10x'"), "{e}");
+        let e = parse("[profiles.trial]\nexpiry = 30\n");
+        assert!(e.contains("expected a string"), "{e}");
+
+        let e = parse("[profiles.trial]\nedition = \"ultimate\"\n");
+        assert!(e.contains("ultimate"), "{e}");
+        let e = parse("[profiles.trial]\nsaets = 5\n");
+        assert!(e.contains("saets"), "{e}");
+        let e = parse("[profile.trial]\nseats = 5\n");
+        assert!(e.contains("profile"), "{e}");
+    }
+}
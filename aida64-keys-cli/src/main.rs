@@ -1,8 +1,1359 @@
-use aida64_keys_lib::{KeyEdition, License};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, IsTerminal, Read, Write};
+use std::num::NonZeroUsize;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Instant;
+
+use aida64_keys_lib::cli::Length;
+use aida64_keys_lib::qr::KeyQr;
+use aida64_keys_lib::rand::rngs::StdRng;
+use aida64_keys_lib::rand::{RngCore, SeedableRng};
+use aida64_keys_lib::scheme::{Aida64, KeyScheme};
+use aida64_keys_lib::{
+    bulk, cli, codec, ChecksumTrace, KeyEdition, KeyError, License, LicenseRecord, SEATS_RANGE,
+};
+use chrono::{Duration, Local, NaiveDate, Utc};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use indicatif::{ProgressBar, ProgressStyle};
+use regex::Regex;
+use serde::Deserialize;
 use strum::IntoEnumIterator;
 
+use crate::config::{Config, Profile};
+use crate::spec::Spec;
+
+mod config;
+mod spec;
+mod tui;
+
+#[derive(Parser)]
+#[command(version, about = "Generates and inspects aida64 license keys")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate license keys
+    Generate(Box<GenerateArgs>),
+    /// Decode a key and print its license fields, or check every line of a file or stdin
+    #[command(alias = "inspect")]
+    Verify {
+        key: Option<String>,
+        /// File with one key per line, `-` or omitting both this and the key reads stdin
+        #[arg(long, conflicts_with = "key")]
+        file: Option<PathBuf>,
+        /// Also print the key as a QR code
+        #[arg(long, requires = "key")]
+        qr: bool,
+        /// Print nothing, the exit code tells the result
+        #[arg(long, short, conflicts_with_all = ["qr", "format"])]
+        quiet: bool,
+        #[arg(long, value_enum, default_value_t, conflicts_with = "qr")]
+        format: VerifyFormat,
+        /// Print the raw segments, their unmasked values and the checksum instead of the fields
+        #[arg(long, requires = "key", conflicts_with_all = ["qr", "quiet", "format"])]
+        raw: bool,
+        /// Check the file again whenever it changes, until interrupted
+        #[arg(long, requires = "file", conflicts_with = "quiet")]
+        watch: bool,
+        /// Check the expiry against this date as YYYY-MM-DD instead of today, for reproducible
+        /// audits
+        #[arg(long, value_parser = parse_date)]
+        as_of: Option<NaiveDate>,
+    },
+    /// Print the checksum computation of a key step by step
+    ExplainChecksum { key: String },
+    /// Propose corrections for a key with a single mistyped character
+    Repair { key: String },
+    /// Print every valid key found in a text file or stdin, like a log or a mail dump
+    Extract {
+        /// File to scan, stdin if omitted or `-`
+        file: Option<PathBuf>,
+    },
+    /// Print counts per edition, seats and expiry and the purchase date range of a key inventory
+    Stats {
+        /// File with the keys, stdin if omitted or `-`
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Count validity and expiries as of this date as YYYY-MM-DD instead of today
+        #[arg(long, value_parser = parse_date)]
+        as_of: Option<NaiveDate>,
+    },
+    /// Print every edition with its slug, index, description and seat limits
+    ListEditions {
+        #[arg(long, value_enum, default_value_t)]
+        format: ListFormat,
+    },
+    /// Open an interactive interface to generate and inspect keys in the terminal
+    Tui,
+    /// Print the completion script for a shell
+    Completions { shell: Shell },
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum ListFormat {
+    /// Aligned columns with a header
+    #[default]
+    Table,
+    /// An array with one object per edition
+    Json,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OutputFormat {
+    /// One key per line, followed by the edition when generating every edition
+    #[default]
+    Plain,
+    /// A header and one row per key with its license fields
+    Csv,
+    /// One JSON object per key with its license fields, flushed as soon as the key is generated
+    Jsonl,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum VerifyFormat {
+    /// Status lines readable by humans
+    #[default]
+    Plain,
+    /// One JSON object per key with its status, exit code and license fields
+    Jsonl,
+}
+
+#[derive(Args, Default)]
+struct GenerateArgs {
+    /// Profile from the config file that fills in the options not passed here
+    #[arg(long)]
+    profile: Option<String>,
+    /// Config file to read the profile from, ~/.config/aida64-keys/config.toml if omitted
+    #[arg(long, requires = "profile")]
+    config: Option<PathBuf>,
+    /// Edition to generate keys for, case insensitive, all if omitted
+    #[arg(long, value_enum, ignore_case = true)]
+    edition: Option<KeyEdition>,
+    /// Seats of every key, 1 if omitted
+    #[arg(long)]
+    seats: Option<i32>,
+    /// Purchase date as YYYY-MM-DD, today if omitted
+    #[arg(long, value_parser = parse_date)]
+    purchase_date: Option<NaiveDate>,
+    /// Days until the license expires, weeks and years with a w or y suffix, a YYYY-MM-DD date or
+    /// max, never if omitted
+    #[arg(long, alias = "expiry-days", value_parser = cli::parse_length)]
+    expiry: Option<Length>,
+    /// Time until the maintenance expires, same format as --expiry, max if omitted
+    #[arg(long, alias = "maintenance-days", value_parser = cli::parse_length)]
+    maintenance: Option<Length>,
+    /// Number of unique keys to generate per edition
+    #[arg(long, default_value_t = 1)]
+    count: usize,
+    /// Re-parse every generated key and fail if any decoded field differs
+    #[arg(long)]
+    verify_after_generate: bool,
+    /// Output format, plain if omitted
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+    /// Print a QR code below every key, only with the plain format
+    #[arg(long)]
+    qr: bool,
+    /// Seed for the random parts of the keys, the same arguments and seed always print the same
+    /// keys
+    #[arg(long, conflicts_with_all = ["prefix", "suffix", "pattern", "jobs"])]
+    seed: Option<u64>,
+    /// Threads generating keys, the number of CPUs if omitted
+    #[arg(long)]
+    jobs: Option<NonZeroUsize>,
+    /// Only print keys starting with these characters (dashes are ignored)
+    #[arg(long, value_parser = parse_prefix)]
+    prefix: Option<String>,
+    /// Only print keys ending with these characters (dashes are ignored)
+    #[arg(long, value_parser = parse_key_chars)]
+    suffix: Option<String>,
+    /// Only print keys matching this regex, matched against the key without dashes
+    #[arg(long)]
+    pattern: Option<Regex>,
+    /// Write the output to this file instead of stdout, replacing its contents
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Append to the --out file instead of replacing it, the CSV header is only written to an
+    /// empty file
+    #[arg(long, requires = "out")]
+    append: bool,
+    /// Never print a key found in this file, like the ledger of previously issued keys. A missing
+    /// file counts as empty
+    #[arg(long)]
+    unique_against: Option<PathBuf>,
+    /// CSV or JSON file with one order per row, prints every row followed by a key for it. Columns
+    /// the rows leave out fall back to the options above
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "count", "format", "qr", "prefix", "suffix", "pattern", "jobs", "append",
+            "unique_against",
+        ]
+    )]
+    spec: Option<PathBuf>,
+}
+
+impl GenerateArgs {
+    /// Fills in the options that weren't passed from the selected profile, exits when the profile
+    /// or the config file can't be read
+    fn apply_profile(&mut self) {
+        let Some(name) = &self.profile else {
+            return;
+        };
+        let Some(path) = self.config.clone().or_else(config::default_path) else {
+            eprintln!("error: no config directory found, pass --config");
+            exit(EXIT_CONFIG);
+        };
+
+        let text = fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("error: {}: {e}", path.display());
+            exit(EXIT_IO);
+        });
+        let mut config = toml::from_str::<Config>(&text).unwrap_or_else(|e| {
+            eprintln!("error: {}: {e}", path.display());
+            exit(EXIT_CONFIG);
+        });
+        let Some(profile) = config.profiles.remove(name) else {
+            eprintln!("error: {} has no profile named '{name}'", path.display());
+            exit(EXIT_CONFIG);
+        };
+
+        self.apply(profile);
+    }
+
+    /// Fills in the options that weren't passed on the command line from `profile`
+    fn apply(&mut self, profile: Profile) {
+        self.edition = self.edition.or(profile.edition);
+        self.seats = self.seats.or(profile.seats);
+        self.expiry = self.expiry.or(profile.expiry);
+        self.maintenance = self.maintenance.or(profile.maintenance);
+        self.format = self.format.or(profile.format);
+        self.verify_after_generate |= profile.verify_after_generate.unwrap_or(false);
+    }
+
+    /// The expiry and maintenance counted from the purchase date, an error when either is out of
+    /// the range a key can encode
+    fn lengths(&self) -> Result<(Option<Duration>, Duration), String> {
+        let purchase = self.purchase_date.unwrap_or_else(today);
+        let expiry = self
+            .expiry
+            .map(|expiry| expiry.resolve(purchase))
+            .transpose()
+            .map_err(|e| format!("expiry: {e}"))?;
+        let maintenance = self
+            .maintenance
+            .unwrap_or(Length::Max)
+            .resolve(purchase)
+            .map_err(|e| format!("maintenance: {e}"))?;
+
+        Ok((expiry, maintenance))
+    }
+
+    fn license<R: RngCore>(&self, edition: KeyEdition, rng: &mut R) -> License {
+        // ? INFO: checked before generating starts
+        let (expiry, maintenance) = self.lengths().unwrap();
+        let license = License::new_with_rng(edition, rng)
+            .with_seats(self.seats.unwrap_or(1))
+            .with_license_expiry(expiry)
+            .with_maintenance_expiry(maintenance);
+
+        match self.purchase_date {
+            Some(date) => license.with_purchase_date(date),
+            None => license,
+        }
+    }
+}
+
+// ? INFO: exit codes, documented in the README, scripts rely on them. 1 to 5 are the reasons
+// verify rejects a key, the sysexits.h codes from 64 on are failures that have nothing to do
+// with a key
+const EXIT_CHECKSUM: i32 = 1;
+const EXIT_LENGTH: i32 = 2;
+const EXIT_UNKNOWN_EDITION: i32 = 3;
+const EXIT_EXPIRED: i32 = 4;
+/// Every other reason a key is rejected, like a character outside the alphabet or a field out
+/// of range
+const EXIT_INVALID: i32 = 5;
+/// Command line usage errors, `EX_USAGE` from sysexits.h
+const EXIT_USAGE: i32 = 64;
+/// A `--spec` file that doesn't parse or has an invalid row, `EX_DATAERR`
+const EXIT_DATA: i32 = 65;
+/// A generated key failed `--verify-after-generate`, `EX_SOFTWARE`
+const EXIT_SOFTWARE: i32 = 70;
+/// A file, stdin or stdout couldn't be read or written, `EX_IOERR`
+const EXIT_IO: i32 = 74;
+/// The config file doesn't parse or has no such profile, `EX_CONFIG`
+const EXIT_CONFIG: i32 = 78;
+
+/// How often `verify --watch` checks whether the file changed
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Runs generating at least this many keys show a progress bar, vanity searches always do
+const PROGRESS_MIN_KEYS: usize = 10_000;
+
+/// Constraints a vanity key has to satisfy, checked against the key without dashes
+struct KeyFilter<'a> {
+    prefix: &'a str,
+    suffix: &'a str,
+    pattern: Option<&'a Regex>,
+}
+
+impl<'a> KeyFilter<'a> {
+    fn from_args(args: &'a GenerateArgs) -> Option<KeyFilter<'a>> {
+        if args.prefix.is_none() && args.suffix.is_none() && args.pattern.is_none() {
+            return None;
+        }
+
+        Some(KeyFilter {
+            prefix: args.prefix.as_deref().unwrap_or_default(),
+            suffix: args.suffix.as_deref().unwrap_or_default(),
+            pattern: args.pattern.as_ref(),
+        })
+    }
+
+    fn matches(&self, key: &str) -> bool {
+        let key = key.replace('-', "");
+        key.starts_with(self.prefix)
+            && key.ends_with(self.suffix)
+            && self.pattern.is_none_or(|pattern| pattern.is_match(&key))
+    }
+}
+
+fn parse_key_chars(value: &str) -> Result<String, String> {
+    let value = value.replace('-', "").to_ascii_uppercase();
+
+    if value.len() > 25 {
+        return Err("keys are only 25 characters long".to_string());
+    }
+
+    match value.bytes().find(|b| !codec::ALPHABET.contains(b)) {
+        Some(b) => Err(format!("'{}' never appears in a key", b as char)),
+        None => Ok(value),
+    }
+}
+
+fn parse_prefix(value: &str) -> Result<String, String> {
+    let prefix = parse_key_chars(value)?;
+
+    // ? INFO: the first two characters encode a single byte, so only the first 8 digits can lead
+    let leading = &codec::ALPHABET[..8];
+    match prefix.bytes().next() {
+        Some(first) if !leading.contains(&first) => {
+            Err(format!("keys always start with one of {}", String::from_utf8_lossy(leading)))
+        },
+        _ => Ok(prefix),
+    }
+}
+
+fn today() -> NaiveDate {
+    Utc::today().naive_utc()
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, chrono::ParseError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+}
+
+fn generate(args: &GenerateArgs) {
+    let editions = match args.edition {
+        Some(edition) => vec![edition],
+        None => KeyEdition::iter().collect(),
+    };
+
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let issued = match &args.unique_against {
+        Some(path) => issued_keys(path),
+        None => HashSet::new(),
+    };
+    let (mut out, empty) = open_output(args);
+
+    let format = args.format.unwrap_or_default();
+    if args.qr && !matches!(format, OutputFormat::Plain) {
+        eprintln!("error: --qr only works with the plain format");
+        exit(EXIT_USAGE);
+    }
+
+    if matches!(format, OutputFormat::Csv) && empty {
+        write_line(&mut out, LicenseRecord::CSV_HEADER);
+    }
+
+    if args.count == 0 {
+        return;
+    }
+
+    for edition in editions {
+        let mut keys = HashSet::new();
+
+        // ? INFO: returns the number of unique keys printed so far for this edition
+        let mut emit = |license: License, key: String| {
+            if args.verify_after_generate {
+                if let Err(e) = license.verify_key(&key) {
+                    eprintln!("error: generated {edition} key {key} failed verification: {e}");
+                    exit(EXIT_SOFTWARE);
+                }
+            }
+
+            if !issued.contains(&key) && keys.insert(key.clone()) {
+                let line = match (format, args.edition) {
+                    (OutputFormat::Csv, _) => {
+                        LicenseRecord::new(key.as_str(), &license).to_csv_row()
+                    },
+                    (OutputFormat::Jsonl, _) => {
+                        serde_json::to_string(&LicenseRecord::new(key.as_str(), &license)).unwrap()
+                    },
+                    (OutputFormat::Plain, Some(_)) => key.clone(),
+                    (OutputFormat::Plain, None) => format!("{key:?} -> {edition}"),
+                };
+                write_line(&mut out, &line);
+
+                // ? INFO: consumers of JSON lines read them while the run is still going
+                if matches!(format, OutputFormat::Jsonl) {
+                    if let Err(e) = out.flush() {
+                        eprintln!("error: {e}");
+                        exit(EXIT_IO);
+                    }
+                }
+
+                if args.qr {
+                    write_qr(&mut out, &key);
+                }
+            }
+
+            keys.len()
+        };
+
+        // ? INFO: seeded runs stay on one thread, the order keys come out in is part of the seed
+        match (KeyFilter::from_args(args), args.seed) {
+            (None, Some(_)) => loop {
+                let license = args.license(edition, &mut rng);
+                let key = license.generate_string_with_rng(true, &mut rng);
+
+                if emit(license, key) >= args.count {
+                    break;
+                }
+            },
+            (filter, _) => generate_parallel(args, edition, filter.as_ref(), emit),
+        }
+    }
+}
+
+/// Generates keys on `--jobs` threads until `emit` reports enough unique keys matching `filter`,
+/// with a progress bar and a throughput summary on stderr for long runs
+fn generate_parallel<F>(
+    args: &GenerateArgs,
+    edition: KeyEdition,
+    filter: Option<&KeyFilter>,
+    mut emit: F,
+) where
+    F: FnMut(License, String) -> usize,
+{
+    let threads = args.jobs.map_or_else(
+        || thread::available_parallelism().map_or(1, |threads| threads.get()),
+        NonZeroUsize::get,
+    );
+    let stop = AtomicBool::new(false);
+    let tried = AtomicU64::new(0);
+    let started = Instant::now();
+
+    let progress = match filter.is_some() || args.count >= PROGRESS_MIN_KEYS {
+        true => ProgressBar::new(args.count as u64),
+        false => ProgressBar::hidden(),
+    };
+    progress.set_style(
+        ProgressStyle::with_template("{prefix}: {wide_bar} {pos}/{len} keys, {msg}").unwrap(),
+    );
+    progress.set_prefix(edition.to_string());
+
+    let on_terminal = args.out.is_none() && io::stdout().is_terminal();
+
+    let mut found = 0;
+    thread::scope(|scope| {
+        // ? INFO: bounded so workers can't outrun the output when every key matches
+        let (tx, rx) = mpsc::sync_channel(1024);
+
+        for _ in 0..threads {
+            let tx = tx.clone();
+            let (stop, tried) = (&stop, &tried);
+
+            scope.spawn(move || {
+                let mut rng = StdRng::from_entropy();
+
+                while !stop.load(Ordering::Relaxed) {
+                    let license = args.license(edition, &mut rng);
+                    let key = license.generate_string_with_rng(true, &mut rng);
+                    tried.fetch_add(1, Ordering::Relaxed);
+
+                    let matches = filter.is_none_or(|filter| filter.matches(&key));
+                    if matches && tx.send((license, key)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        while found < args.count {
+            match rx.recv_timeout(std::time::Duration::from_millis(250)) {
+                // ? INFO: only keys printed to the terminal have to get out of the bar's way
+                Ok((license, key)) if on_terminal => {
+                    found = progress.suspend(|| emit(license, key))
+                },
+                Ok((license, key)) => found = emit(license, key),
+                Err(RecvTimeoutError::Timeout) => {},
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            progress.set_position(found as u64);
+            progress.set_message(format!("tried {}", tried.load(Ordering::Relaxed)));
+        }
+
+        stop.store(true, Ordering::Relaxed);
+        drop(rx);
+    });
+
+    if !progress.is_hidden() {
+        progress.finish_and_clear();
+
+        let elapsed = started.elapsed();
+        eprintln!(
+            "{edition}: {found} keys in {:.2}s ({:.0} keys/s), tried {}",
+            elapsed.as_secs_f64(),
+            found as f64 / elapsed.as_secs_f64(),
+            tried.load(Ordering::Relaxed)
+        );
+    }
+}
+
+/// Where generated keys go, and whether it was empty before, so a CSV header is needed
+fn open_output(args: &GenerateArgs) -> (Box<dyn Write>, bool) {
+    let Some(path) = &args.out else {
+        return (Box::new(io::stdout()), true);
+    };
+
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(args.append)
+        .truncate(!args.append)
+        .open(path)
+        .and_then(|file| Ok((file.metadata()?.len() == 0, file)));
+
+    match file {
+        Ok((empty, file)) => (Box::new(BufWriter::new(file)), empty),
+        Err(e) => {
+            eprintln!("error: {}: {e}", path.display());
+            exit(EXIT_IO);
+        },
+    }
+}
+
+/// One key per order of the spec at `path`, the rows fall back to `args` for the fields they
+/// leave out
+fn generate_spec(args: &GenerateArgs, path: &Path) {
+    let data = fs::read(path).unwrap_or_else(|e| {
+        eprintln!("error: {}: {e}", path.display());
+        exit(EXIT_IO);
+    });
+    let spec = Spec::parse(&data, Spec::is_json(path)).unwrap_or_else(|e| {
+        eprintln!("error: {}: {e}", path.display());
+        exit(EXIT_DATA);
+    });
+
+    // ? INFO: every row is checked before the first key is generated, a bad row doesn't leave
+    // half a batch behind
+    let orders = (0..spec.len())
+        .map(|idx| {
+            let order = spec.order(idx)?;
+            let row = spec.row_number(idx);
+            let edition = order.edition.or(args.edition).ok_or_else(|| {
+                format!("row {row}: no edition, add an edition column or pass --edition")
+            })?;
+            let order = GenerateArgs {
+                seats: order.seats.or(args.seats),
+                purchase_date: order.purchase_date.or(args.purchase_date),
+                expiry: order.expiry.or(args.expiry),
+                maintenance: order.maintenance.or(args.maintenance),
+                ..Default::default()
+            };
+
+            order.lengths().map(|_| (edition, order)).map_err(|e| format!("row {row}: {e}"))
+        })
+        .collect::<Result<Vec<_>, String>>()
+        .unwrap_or_else(|e| {
+            eprintln!("error: {}: {e}", path.display());
+            exit(EXIT_DATA);
+        });
+
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let keys = orders
+        .iter()
+        .map(|(edition, order)| {
+            let license = order.license(*edition, &mut rng);
+            let key = license.generate_string_with_rng(true, &mut rng);
+
+            if args.verify_after_generate {
+                if let Err(e) = license.verify_key(&key) {
+                    eprintln!("error: generated {edition} key {key} failed verification: {e}");
+                    exit(EXIT_SOFTWARE);
+                }
+            }
+
+            key
+        })
+        .collect::<Vec<_>>();
+
+    let (mut out, _) = open_output(args);
+    if let Err(e) = spec.write(&keys, &mut out) {
+        eprintln!("error: {e}");
+        exit(EXIT_IO);
+    }
+}
+
+/// Every key in `path`, in the dashed form keys are generated in
+fn issued_keys(path: &Path) -> HashSet<String> {
+    let text = match fs::read(path) {
+        Ok(data) => String::from_utf8_lossy(&data).into_owned(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            eprintln!("error: {}: {e}", path.display());
+            exit(EXIT_IO);
+        },
+    };
+
+    bulk::find_keys_in_text(&text)
+        .into_iter()
+        .filter_map(|candidate| codec::canonicalize(candidate.text).ok())
+        .collect()
+}
+
+fn write_line(out: &mut dyn Write, line: &str) {
+    if let Err(e) = writeln!(out, "{line}") {
+        eprintln!("error: {e}");
+        exit(EXIT_IO);
+    }
+}
+
+fn write_qr(out: &mut dyn Write, key: &str) {
+    match KeyQr::new(key) {
+        Ok(qr) => write_line(out, &qr.to_unicode()),
+        Err(e) => eprintln!("error: can't render {key} as a QR code: {e}"),
+    }
+}
+
+const GREEN: &str = "32";
+const RED: &str = "31";
+
+/// Whether stdout gets colors, only when it is a terminal and `NO_COLOR` isn't set
+fn use_color() -> bool {
+    static USE_COLOR: OnceLock<bool> = OnceLock::new();
+
+    *USE_COLOR.get_or_init(|| {
+        io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+    })
+}
+
+/// `text` in the ANSI color `color` when colors are enabled
+fn paint(color: &str, text: &str) -> String {
+    match use_color() {
+        true => format!("\x1b[{color}m{text}\x1b[0m"),
+        false => text.to_string(),
+    }
+}
+
+/// Exit code of `verify` for a parsed key, 0 when the license is valid as of `as_of`
+fn verify_code(license: &Result<License, KeyError>, as_of: NaiveDate) -> i32 {
+    match license {
+        Ok(license) if license.is_valid_key_at(as_of) => 0,
+        Ok(license) if license.is_expired(as_of) => EXIT_EXPIRED,
+        Ok(_) => EXIT_INVALID,
+        Err(KeyError::InvalidChecksum { .. }) => EXIT_CHECKSUM,
+        Err(KeyError::InvalidLength { .. }) => EXIT_LENGTH,
+        Err(KeyError::UnknownEdition { .. }) => EXIT_UNKNOWN_EDITION,
+        Err(_) => EXIT_INVALID,
+    }
+}
+
+/// `key` with its status, exit code and, when it decodes, its license fields
+fn verify_json(key: &str, license: &Result<License, KeyError>, code: i32) -> serde_json::Value {
+    let mut value = match license {
+        Ok(license) => serde_json::to_value(LicenseRecord::new(key, license)).unwrap(),
+        Err(_) => serde_json::json!({ "key": key }),
+    };
+
+    value["valid"] = (code == 0).into();
+    value["code"] = code.into();
+    match license {
+        Err(e) => value["error"] = e.to_string().into(),
+        Ok(_) if code != 0 => value["error"] = KeyError::InvalidLicense.to_string().into(),
+        Ok(_) => {},
+    }
+
+    value
+}
+
+fn verify(key: &str, qr: bool, quiet: bool, format: VerifyFormat, as_of: NaiveDate) {
+    let parsed = License::from_key(key);
+    let code = verify_code(&parsed, as_of);
+
+    let license = match parsed {
+        _ if quiet => exit(code),
+        parsed if matches!(format, VerifyFormat::Jsonl) => {
+            println!("{}", verify_json(key, &parsed, code));
+            exit(code);
+        },
+        Ok(license) => license,
+        Err(e) => {
+            eprintln!("error: {e}");
+            exit(code);
+        },
+    };
+
+    let format_date = |date: NaiveDate| date.format("%Y-%m-%d").to_string();
+
+    let expiry = match (license.expiry_date(), license.expiry()) {
+        (Some(date), Some(expiry)) => format!("{} ({} days)", format_date(date), expiry.num_days()),
+        _ => "never".to_string(),
+    };
+
+    println!("{}\n", license.summary());
+    println!("edition:            {}", license.edition());
+    println!("seats:              {}", license.seats());
+    println!("purchase date:      {}", format_date(license.purchase_date()));
+    println!("expiry:             {expiry}");
+    println!(
+        "maintenance expiry: {} ({} days)",
+        format_date(license.maintenance_expiry_date()),
+        license.maintenance_expiry().num_days()
+    );
+
+    match code {
+        0 => println!("valid:              {}", paint(GREEN, "yes")),
+        _ => println!("valid:              {}", paint(RED, "no")),
+    }
+
+    if qr {
+        println!();
+        write_qr(&mut io::stdout(), key);
+    }
+
+    exit(code);
+}
+
+fn verify_raw(key: &str, as_of: NaiveDate) {
+    const NAMES: [&str; 9] = [
+        "edition",
+        "component 1",
+        "component 2",
+        "component 3",
+        "seats",
+        "purchase date",
+        "expiry",
+        "maintenance",
+        "base",
+    ];
+
+    let code = verify_code(&License::from_key(key), as_of);
+    let (parts, trace) = match License::decode_parts(key)
+        .and_then(|parts| ChecksumTrace::new(key).map(|trace| (parts, trace)))
+    {
+        Ok(parts) => parts,
+        Err(e) => {
+            eprintln!("error: {e}");
+            exit(code);
+        },
+    };
+    let normalized = codec::canonicalize(key).unwrap().replace('-', "");
+
+    println!(
+        "{:<13}  {:<5}  {:>7}  {:>8}  {:>6}  {:>8}",
+        "segment", "chars", "raw", "base", "xor", "unmasked"
+    );
+    for (idx, range) in Aida64::SEGMENTS.into_iter().enumerate() {
+        let chars = &normalized[range];
+        let raw = parts.segments[idx];
+
+        match Aida64::MASKS.get(idx) {
+            Some(mask) => println!(
+                "{:<13}  {chars:<5}  {raw:>7}  {:>#8x}  {:>#6x}  {:>8}",
+                NAMES[idx],
+                parts.base & mask.base,
+                mask.xor,
+                parts.unmasked[idx]
+            ),
+            None => println!("{:<13}  {chars:<5}  {raw:>7}", NAMES[idx]),
+        }
+    }
+
+    println!();
+    println!("checksum:     0x{:04X} ({})", trace.checksum, trace.checksum);
+    println!("check char:   {} (expected {})", parts.checksum as char, trace.expected as char);
+
+    exit(code);
+}
+
+/// Contents of `file`, or of stdin when it's omitted or `-`
+fn read_input(file: Option<&Path>) -> Result<Vec<u8>, String> {
+    match file {
+        Some(path) if path != Path::new("-") => {
+            fs::read(path).map_err(|e| format!("{}: {e}", path.display()))
+        },
+        _ => {
+            let mut data = Vec::new();
+            io::stdin().read_to_end(&mut data).map(|_| data).map_err(|e| format!("stdin: {e}"))
+        },
+    }
+}
+
+/// Checks every line of `file` like [`verify_lines`], returns the exit code
+fn verify_many(file: Option<&Path>, quiet: bool, format: VerifyFormat, as_of: NaiveDate) -> i32 {
+    match read_input(file) {
+        Ok(data) => verify_lines(&data, quiet, format, as_of),
+        Err(e) => {
+            eprintln!("error: {e}");
+            EXIT_IO
+        },
+    }
+}
+
+/// Re-runs [`verify_lines`] on `path` whenever it changes, until interrupted
+fn verify_watch(path: &Path, format: VerifyFormat, as_of: NaiveDate) {
+    let watched = watch_file(path, WATCH_INTERVAL, |data| {
+        if matches!(format, VerifyFormat::Plain) {
+            println!("== {} at {}", path.display(), Local::now().format("%H:%M:%S"));
+        }
+        verify_lines(data, false, format, as_of);
+        println!();
+
+        ControlFlow::Continue(())
+    });
+
+    if let Err(e) = watched {
+        eprintln!("error: {e}");
+        exit(EXIT_IO);
+    }
+}
+
+/// Calls `on_change` with the contents of `path` right away and whenever its modification time
+/// or length changes, checking every `interval` until `on_change` breaks
+fn watch_file(
+    path: &Path,
+    interval: std::time::Duration,
+    mut on_change: impl FnMut(&[u8]) -> ControlFlow<()>,
+) -> Result<(), String> {
+    // ? INFO: the length catches writes within the same modification time on filesystems that
+    // only store whole seconds
+    let stamp =
+        || fs::metadata(path).and_then(|metadata| Ok((metadata.modified()?, metadata.len())));
+    stamp().map_err(|e| format!("{}: {e}", path.display()))?;
+
+    let mut last = None;
+    loop {
+        match stamp() {
+            Ok(stamp) if last == Some(stamp) => {},
+            Ok(stamp) => {
+                last = Some(stamp);
+                match fs::read(path) {
+                    Ok(data) if on_change(&data).is_break() => return Ok(()),
+                    Ok(_) => {},
+                    Err(e) => eprintln!("error: {}: {e}", path.display()),
+                }
+            },
+            // ? INFO: editors can replace the file on save, it's checked again once it's back
+            Err(e) if last.is_some() => {
+                eprintln!("error: {}: {e}", path.display());
+                last = None;
+            },
+            Err(_) => {},
+        }
+
+        thread::sleep(interval);
+    }
+}
+
+/// Checks every line of `data`, returns the exit code of the first invalid key
+fn verify_lines(data: &[u8], quiet: bool, format: VerifyFormat, as_of: NaiveDate) -> i32 {
+    let (mut valid, mut invalid) = (0, 0);
+    let mut first_code = 0;
+    for (key, license) in License::parse_all(data) {
+        let code = verify_code(&license, as_of);
+
+        if matches!(format, VerifyFormat::Jsonl) {
+            if first_code == 0 {
+                first_code = code;
+            }
+            println!("{}", verify_json(&key, &license, code));
+            continue;
+        }
+        let license = license.and_then(|license| match code {
+            0 => Ok(license),
+            _ => Err(KeyError::InvalidLicense),
+        });
+
+        match license {
+            Ok(_) => {
+                valid += 1;
+                if !quiet {
+                    println!("{}  {key}", paint(GREEN, &format!("{:<7}", "ok")));
+                }
+            },
+            Err(e) => {
+                invalid += 1;
+                if first_code == 0 {
+                    first_code = code;
+                }
+                if !quiet {
+                    println!("{}  {key:<29}  {e}", paint(RED, &format!("{:<7}", "invalid")));
+                }
+            },
+        }
+    }
+
+    if !quiet && matches!(format, VerifyFormat::Plain) {
+        println!();
+        println!(
+            "{} keys, {} valid, {} invalid",
+            valid + invalid,
+            paint(GREEN, &valid.to_string()),
+            paint(RED, &invalid.to_string())
+        );
+    }
+
+    first_code
+}
+
+fn extract(file: Option<&Path>) {
+    let data = read_input(file).unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        exit(EXIT_IO);
+    });
+    let text = String::from_utf8_lossy(&data);
+
+    for candidate in bulk::find_keys_in_text(&text) {
+        if bulk::validate_key(candidate.text).is_ok() {
+            println!("{}", candidate.text);
+        }
+    }
+}
+
+fn stats(file: Option<&Path>, today: NaiveDate) {
+    const SEAT_BUCKETS: [(i32, i32); 6] =
+        [(1, 1), (2, 5), (6, 10), (11, 25), (26, 100), (101, 797)];
+    const EXPIRY_BUCKETS: [&str; 5] =
+        ["never", "expired", "within 30 days", "within 1 year", "later"];
+
+    let data = read_input(file).unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        exit(EXIT_IO);
+    });
+
+    let mut undecodable = 0;
+    let mut not_valid = 0;
+    let mut editions = [0; 4];
+    let mut seats = [0; SEAT_BUCKETS.len()];
+    let mut expiries = [0; EXPIRY_BUCKETS.len()];
+    let mut purchase_dates: Option<(NaiveDate, NaiveDate)> = None;
+
+    let licenses = License::parse_all(&data[..]).filter_map(|(_, license)| match license {
+        Ok(license) => Some(license),
+        Err(_) => {
+            undecodable += 1;
+            None
+        },
+    });
+
+    let mut decoded = 0;
+    for license in licenses {
+        decoded += 1;
+        not_valid += usize::from(!license.is_valid_key_at(today));
+        editions[license.edition() as usize] += 1;
+
+        if let Some(bucket) =
+            SEAT_BUCKETS.iter().position(|&(min, max)| (min..=max).contains(&license.seats()))
+        {
+            seats[bucket] += 1;
+        }
+
+        let expiry = match license.remaining_days(today) {
+            None => 0,
+            Some(days) if days <= 0 => 1,
+            Some(days) if days <= 30 => 2,
+            Some(days) if days <= 365 => 3,
+            Some(_) => 4,
+        };
+        expiries[expiry] += 1;
+
+        let date = license.purchase_date();
+        purchase_dates = Some(match purchase_dates {
+            Some((earliest, latest)) => (earliest.min(date), latest.max(date)),
+            None => (date, date),
+        });
+    }
+
+    println!(
+        "{} keys, {decoded} decoded, {undecodable} invalid, {not_valid} decoded but not valid",
+        decoded + undecodable
+    );
+
+    println!();
+    println!("edition");
+    for edition in KeyEdition::all() {
+        println!("  {:<16} {:>6}", edition.to_string(), editions[edition as usize]);
+    }
+
+    println!();
+    println!("seats");
+    for (&(min, max), count) in SEAT_BUCKETS.iter().zip(seats) {
+        let range = match min == max {
+            true => min.to_string(),
+            false => format!("{min}-{max}"),
+        };
+        println!("  {range:<16} {count:>6}");
+    }
+
+    println!();
+    println!("expiry");
+    for (bucket, count) in EXPIRY_BUCKETS.iter().zip(expiries) {
+        println!("  {bucket:<16} {count:>6}");
+    }
+
+    println!();
+    match purchase_dates {
+        Some((earliest, latest)) => println!("purchase dates     {earliest} to {latest}"),
+        None => println!("purchase dates     none"),
+    }
+}
+
+fn list_editions(format: ListFormat) {
+    match format {
+        ListFormat::Table => {
+            println!("{:<9}  {:>5}  {:<13}  {:>5}  description", "slug", "index", "name", "seats");
+            for edition in KeyEdition::all() {
+                println!(
+                    "{:<9}  {:>5}  {:<13}  {:>5}  {}",
+                    edition.slug(),
+                    edition as i32,
+                    edition.to_string(),
+                    format!("{}-{}", SEATS_RANGE.start(), edition.max_seats()),
+                    edition.description()
+                );
+            }
+        },
+        ListFormat::Json => {
+            let editions = KeyEdition::all().map(|edition| {
+                serde_json::json!({
+                    "slug": edition.slug(),
+                    "index": edition as i32,
+                    "name": edition.to_string(),
+                    "description": edition.description(),
+                    "min_seats": SEATS_RANGE.start(),
+                    "max_seats": edition.max_seats(),
+                })
+            });
+            println!("{}", serde_json::to_string_pretty(&editions).unwrap());
+        },
+    }
+}
+
+fn repair(key: &str) {
+    let typed = key.bytes().filter(u8::is_ascii_alphanumeric).collect::<Vec<u8>>();
+    let candidates = License::repair_key(key);
+
+    match candidates.first() {
+        None => {
+            eprintln!("error: no single character change turns {key} into a valid key");
+            exit(EXIT_INVALID);
+        },
+        // ? INFO: repair_key returns a valid key unchanged as its only candidate
+        Some(candidate)
+            if candidate.bytes().filter(u8::is_ascii_alphanumeric).eq(typed.iter().copied()) =>
+        {
+            println!("{candidate} is already valid");
+            return;
+        },
+        Some(_) => {},
+    }
+
+    println!(
+        "{:<29}  {:<7}  {:<12}  {:>5}  {:<10}  {:<10}  {:<11}",
+        "key", "change", "edition", "seats", "purchased", "expires", "maintenance"
+    );
+    for candidate in &candidates {
+        let license = License::from_key(candidate).unwrap();
+        let record = LicenseRecord::new(candidate.as_str(), &license);
+
+        // ? INFO: candidates differ from the typed key in exactly one character
+        let change = candidate
+            .bytes()
+            .filter(u8::is_ascii_alphanumeric)
+            .zip(&typed)
+            .enumerate()
+            .find(|(_, (c, &t))| *c != t)
+            .map(|(idx, (c, t))| format!("{}: {}>{}", idx + 1, *t as char, c as char))
+            .unwrap_or_default();
+
+        println!(
+            "{:<29}  {change:<7}  {:<12}  {:>5}  {:<10}  {:<10}  {:<11}",
+            record.key,
+            record.edition.to_string(),
+            record.seats,
+            record.purchase_date,
+            record.expiry_date.map(|date| date.to_string()).unwrap_or_else(|| "never".into()),
+            record.maintenance_date
+        );
+    }
+}
+
+fn explain_checksum(key: &str) {
+    let trace = match ChecksumTrace::new(key) {
+        Ok(trace) => trace,
+        Err(e) => {
+            eprintln!("error: {e}");
+            exit(verify_code(&Err(e), today()));
+        },
+    };
+
+    println!("register starts at 0x0000, for each byte: reg ^= byte << 8, then 8x shift left (xor 0x8201 when bit 15 was set)");
+    println!();
+    println!("{:>3}  {:>4}  {:>4}  {:>10}", "#", "char", "byte", "register");
+    for (idx, step) in trace.steps.iter().enumerate() {
+        println!(
+            "{idx:>3}  {:>4}  0x{:02X}  0x{:08X}",
+            step.byte as char, step.byte, step.register
+        );
+    }
+
+    println!();
+    println!("register & 0xFFFF = 0x{:04X} ({})", trace.register, trace.register);
+    println!("register % 0x9987 = 0x{:04X} ({})", trace.checksum, trace.checksum);
+    println!("base-34 encoded   = {}", String::from_utf8_lossy(&trace.encoded));
+    println!("check character   = {} (middle encoded character)", trace.expected as char);
+
+    match trace.found {
+        Some(found) if trace.is_valid() => {
+            println!("key check char    = {} (valid)", found as char)
+        },
+        Some(found) => {
+            println!("key check char    = {} (invalid)", found as char);
+            exit(EXIT_CHECKSUM);
+        },
+        None => {},
+    }
+}
+
+/// Exit code for a failed command line parse, 0 when clap printed `--help` or `--version`
+fn parse_error_code(e: &clap::Error) -> i32 {
+    // ? INFO: clap exits with 2 on usage errors, which verify uses for keys of the wrong length
+    match e.use_stderr() {
+        true => EXIT_USAGE,
+        false => 0,
+    }
+}
+
 fn main() {
-    for edition in KeyEdition::iter() {
-        println!("{:?} -> {edition}", License::new(edition).generate_string(true));
+    let cli = Cli::try_parse().unwrap_or_else(|e| {
+        let _ = e.print();
+        exit(parse_error_code(&e));
+    });
+
+    match cli.command {
+        Some(Command::Generate(mut args)) => {
+            args.apply_profile();
+            if let Err(e) = args.lengths() {
+                eprintln!("error: {e}");
+                exit(EXIT_USAGE);
+            }
+            match &args.spec {
+                Some(path) => generate_spec(&args, path),
+                None => generate(&args),
+            }
+        },
+        Some(Command::Verify { key: Some(key), raw: true, as_of, .. }) => {
+            verify_raw(&key, as_of.unwrap_or_else(today))
+        },
+        Some(Command::Verify { key: Some(key), qr, quiet, format, as_of, .. }) => {
+            verify(&key, qr, quiet, format, as_of.unwrap_or_else(today))
+        },
+        Some(Command::Verify { file: Some(file), watch: true, format, as_of, .. }) => {
+            verify_watch(&file, format, as_of.unwrap_or_else(today))
+        },
+        Some(Command::Verify { key: None, file, quiet, format, as_of, .. }) => {
+            exit(verify_many(file.as_deref(), quiet, format, as_of.unwrap_or_else(today)))
+        },
+        Some(Command::ExplainChecksum { key }) => explain_checksum(&key),
+        Some(Command::Repair { key }) => repair(&key),
+        Some(Command::Extract { file }) => extract(file.as_deref()),
+        Some(Command::Stats { file, as_of }) => stats(file.as_deref(), as_of.unwrap_or_else(today)),
+        Some(Command::ListEditions { format }) => list_editions(format),
+        Some(Command::Tui) => {
+            if let Err(e) = tui::run() {
+                eprintln!("error: {e}");
+                exit(EXIT_IO);
+            }
+        },
+        Some(Command::Completions { shell }) => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_string();
+            clap_complete::generate(shell, &mut command, name, &mut io::stdout());
+        },
+        None => generate(&GenerateArgs { count: 1, ..Default::default() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_codes() {
+        let purchase_date = NaiveDate::from_ymd(2024, 1, 1);
+        let license = License::new(KeyEdition::Extreme)
+            .with_purchase_date(purchase_date)
+            .with_license_expiry(Some(Duration::days(30)));
+        let key = license.generate_string(true);
+
+        let valid = License::from_key(&key);
+        assert_eq!(verify_code(&valid, purchase_date), 0);
+        assert_eq!(verify_code(&valid, purchase_date + Duration::days(29)), 0);
+        assert_eq!(verify_code(&valid, purchase_date + Duration::days(30)), EXIT_EXPIRED);
+        assert_eq!(verify_code(&valid, purchase_date + Duration::days(3650)), EXIT_EXPIRED);
+
+        let mut typo = key.clone();
+        let check = match typo.ends_with('A') {
+            true => "B",
+            false => "A",
+        };
+        typo.replace_range(28..29, check);
+        let checksum = License::from_key(&typo);
+        assert!(matches!(checksum, Err(KeyError::InvalidChecksum { .. })));
+        assert_eq!(verify_code(&checksum, purchase_date), EXIT_CHECKSUM);
+
+        assert_eq!(verify_code(&License::from_key(&key[..23]), purchase_date), EXIT_LENGTH);
+        assert_eq!(verify_code(&License::from_key("garbage"), purchase_date), EXIT_LENGTH);
+        let unknown_edition = Err(KeyError::UnknownEdition { index: Some(9) });
+        assert_eq!(verify_code(&unknown_edition, purchase_date), EXIT_UNKNOWN_EDITION);
+
+        let mut character = key.clone();
+        character.replace_range(0..1, "0");
+        let character = License::from_key(&character);
+        assert!(matches!(character, Err(KeyError::InvalidCharacter { .. })));
+        assert_eq!(verify_code(&character, purchase_date), EXIT_INVALID);
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "{key}").unwrap();
+        let path = file.path().to_path_buf();
+        assert_eq!(verify_many(Some(&path), true, VerifyFormat::Plain, purchase_date), 0);
+        writeln!(file, "{typo}").unwrap();
+        assert_eq!(
+            verify_many(Some(&path), true, VerifyFormat::Plain, purchase_date),
+            EXIT_CHECKSUM
+        );
+
+        drop(file);
+        assert_eq!(verify_many(Some(&path), true, VerifyFormat::Plain, purchase_date), EXIT_IO);
+    }
+
+    #[test]
+    fn profiles() {
+        let config: Config = toml::from_str(
+            r#"
+            [profiles.resale]
+            edition = "extreme"
+            seats = 5
+            expiry = "1y"
+            format = "csv"
+            verify_after_generate = true
+            "#,
+        )
+        .unwrap();
+        let profile = || config.profiles.get("resale").cloned().unwrap();
+
+        let mut args = GenerateArgs::default();
+        args.apply(profile());
+        assert_eq!(args.edition, Some(KeyEdition::Extreme));
+        assert_eq!(args.seats, Some(5));
+        assert_eq!(args.expiry, Some(Length::Years(1)));
+        assert_eq!(args.maintenance, None);
+        assert!(matches!(args.format, Some(OutputFormat::Csv)));
+        assert!(args.verify_after_generate);
+
+        let mut args = GenerateArgs {
+            edition: Some(KeyEdition::Business),
+            seats: Some(1),
+            expiry: Some(Length::Max),
+            format: Some(OutputFormat::Jsonl),
+            ..Default::default()
+        };
+        args.apply(profile());
+        assert_eq!(args.edition, Some(KeyEdition::Business));
+        assert_eq!(args.seats, Some(1));
+        assert_eq!(args.expiry, Some(Length::Max));
+        assert!(matches!(args.format, Some(OutputFormat::Jsonl)));
+
+        // ? INFO: a profile can't turn the flag off once it's passed
+        let mut args = GenerateArgs { verify_after_generate: true, ..Default::default() };
+        args.apply(Profile { verify_after_generate: Some(false), ..Default::default() });
+        assert!(args.verify_after_generate);
+    }
+
+    #[test]
+    fn watch() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "first").unwrap();
+
+        // ? INFO: the writes mostly share a modification time, the length still tells them apart
+        let mut runs = Vec::new();
+        let interval = std::time::Duration::from_millis(5);
+        let path = file.path().to_path_buf();
+        watch_file(&path, interval, |data| {
+            runs.push(String::from_utf8_lossy(data).into_owned());
+            match runs.len() {
+                1 => write!(file, "\nsecond").unwrap(),
+                2 => write!(file, "\nthird").unwrap(),
+                _ => return ControlFlow::Break(()),
+            }
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(runs, ["first", "first\nsecond", "first\nsecond\nthird"]);
+
+        drop(file);
+        assert!(watch_file(&path, interval, |_| ControlFlow::Break(())).is_err());
+    }
+
+    #[test]
+    fn parse_error_codes() {
+        let code = |args: &[&str]| {
+            let args = ["aida64-keys-cli"].iter().chain(args);
+            Cli::try_parse_from(args).map(|_| ()).map_err(|e| parse_error_code(&e))
+        };
+
+        assert_eq!(code(&["verify", "--bogus"]), Err(EXIT_USAGE));
+        assert_eq!(code(&["verify", "--as-of", "yesterday"]), Err(EXIT_USAGE));
+        assert_eq!(code(&["verify", "--watch"]), Err(EXIT_USAGE));
+        assert_eq!(code(&["bogus"]), Err(EXIT_USAGE));
+        assert_eq!(code(&["--help"]), Err(0));
+        assert_eq!(code(&["--version"]), Err(0));
+        assert_eq!(code(&["verify", "--file", "keys.txt", "--watch"]), Ok(()));
     }
 }
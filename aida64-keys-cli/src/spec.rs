@@ -0,0 +1,276 @@
+//! Order specs for `generate --spec`, one key per row of a CSV file or object of a JSON array
+//!
+//! The `edition`, `seats`, `purchase_date`, `expiry` and `maintenance` columns take the same
+//! values as the generate options and fall back to them when missing or empty. Every column is
+//! written back out as is, followed by the generated key.
+//!
+//! ```csv
+//! order,customer,edition,seats,purchase_date,expiry
+//! 1001,ACME,extreme,5,2024-01-01,1y
+//! 1002,Initech,engineer,1,,90d
+//! ```
+
+use std::io::Write;
+use std::path::Path;
+
+use aida64_keys_lib::cli::{self, Length};
+use aida64_keys_lib::{KeyEdition, SEATS_RANGE};
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+
+pub enum Spec {
+    Csv { headers: csv::StringRecord, rows: Vec<csv::StringRecord> },
+    Json(Vec<Map<String, Value>>),
+}
+
+/// License fields of one row, `None` where the row leaves them to the generate options
+#[derive(Default)]
+pub struct Order {
+    pub edition: Option<KeyEdition>,
+    pub seats: Option<i32>,
+    pub purchase_date: Option<NaiveDate>,
+    pub expiry: Option<Length>,
+    pub maintenance: Option<Length>,
+}
+
+impl Spec {
+    /// Whether `path` holds a JSON spec, it ends in `.json`, or a CSV spec with a header row
+    pub fn is_json(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+    }
+
+    /// Parses the contents of a spec file, see [`Spec::is_json`]
+    pub fn parse(data: &[u8], json: bool) -> Result<Spec, String> {
+        match json {
+            true => serde_json::from_slice(data).map(Spec::Json).map_err(|e| e.to_string()),
+            false => {
+                let mut reader = csv::Reader::from_reader(data);
+                let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+                let rows = reader.records().collect::<Result<_, _>>().map_err(|e| e.to_string())?;
+
+                Ok(Spec::Csv { headers, rows })
+            },
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Spec::Csv { rows, .. } => rows.len(),
+            Spec::Json(rows) => rows.len(),
+        }
+    }
+
+    /// Column `name` of row `idx`, `None` when it's missing or empty
+    fn field(&self, idx: usize, name: &str) -> Option<String> {
+        let value = match self {
+            Spec::Csv { headers, rows } => headers
+                .iter()
+                .position(|header| header.trim() == name)
+                .and_then(|column| rows[idx].get(column))
+                .map(|value| value.trim().to_string()),
+            Spec::Json(rows) => match rows[idx].get(name) {
+                Some(Value::String(value)) => Some(value.trim().to_string()),
+                Some(Value::Null) | None => None,
+                Some(value) => Some(value.to_string()),
+            },
+        };
+
+        value.filter(|value| !value.is_empty())
+    }
+
+    /// Number of row `idx` in the file, counting the CSV header
+    pub fn row_number(&self, idx: usize) -> usize {
+        match self {
+            Spec::Csv { .. } => idx + 2,
+            Spec::Json(_) => idx + 1,
+        }
+    }
+
+    /// The license fields of row `idx`
+    pub fn order(&self, idx: usize) -> Result<Order, String> {
+        let error =
+            |column: &str, e: String| format!("row {}, {column}: {e}", self.row_number(idx));
+
+        let edition = self
+            .field(idx, "edition")
+            .map(|value| {
+                <KeyEdition as ValueEnum>::from_str(&value, true).map_err(|e| error("edition", e))
+            })
+            .transpose()?;
+        let seats = self
+            .field(idx, "seats")
+            .map(|value| match value.parse() {
+                Ok(seats) if SEATS_RANGE.contains(&seats) => Ok(seats),
+                _ => Err(error(
+                    "seats",
+                    format!(
+                        "expected {} to {} seats, not '{value}'",
+                        SEATS_RANGE.start(),
+                        SEATS_RANGE.end()
+                    ),
+                )),
+            })
+            .transpose()?;
+        let purchase_date = self
+            .field(idx, "purchase_date")
+            .map(|value| {
+                NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                    .map_err(|e| error("purchase_date", format!("'{value}': {e}")))
+            })
+            .transpose()?;
+        let length = |column: &str| {
+            self.field(idx, column)
+                .map(|value| cli::parse_length(&value).map_err(|e| error(column, e)))
+                .transpose()
+        };
+
+        Ok(Order {
+            edition,
+            seats,
+            purchase_date,
+            expiry: length("expiry")?,
+            maintenance: length("maintenance")?,
+        })
+    }
+
+    /// Writes every row followed by its key, in the format the spec was read in
+    pub fn write(&self, keys: &[String], out: &mut dyn Write) -> Result<(), String> {
+        match self {
+            Spec::Csv { headers, rows } => {
+                let mut writer = csv::Writer::from_writer(out);
+                writer.write_record(headers.iter().chain(["key"])).map_err(|e| e.to_string())?;
+
+                for (row, key) in rows.iter().zip(keys) {
+                    writer
+                        .write_record(row.iter().chain([key.as_str()]))
+                        .map_err(|e| e.to_string())?;
+                }
+                writer.flush().map_err(|e| e.to_string())
+            },
+            Spec::Json(rows) => {
+                let rows = rows
+                    .iter()
+                    .zip(keys)
+                    .map(|(row, key)| {
+                        let mut row = row.clone();
+                        row.insert("key".to_string(), key.clone().into());
+                        row
+                    })
+                    .collect::<Vec<_>>();
+
+                serde_json::to_writer_pretty(&mut *out, &rows).map_err(|e| e.to_string())?;
+                writeln!(out).and_then(|_| out.flush()).map_err(|e| e.to_string())
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn csv(text: &str) -> Spec {
+        Spec::parse(text.as_bytes(), false).unwrap()
+    }
+
+    fn json(text: &str) -> Spec {
+        Spec::parse(text.as_bytes(), true).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        assert!(Spec::is_json(Path::new("orders.JSON")));
+        assert!(!Spec::is_json(Path::new("orders.csv")));
+        assert!(Spec::parse(b"[{\"edition\": ", true).is_err());
+        assert!(Spec::parse(b"{\"edition\": \"extreme\"}", true).is_err());
+        assert!(Spec::parse(b"order,edition\n1001,extreme,5\n", false).is_err());
+    }
+
+    #[test]
+    fn valid() {
+        let spec = csv("order,edition,seats,purchase_date,expiry,maintenance\n\
+                        1001,extreme,5,2024-01-01,1y,90d\n\
+                        1002, engineer ,,,,\n");
+        assert_eq!(spec.len(), 2);
+
+        let order = spec.order(0).unwrap();
+        assert_eq!(order.edition, Some(KeyEdition::Extreme));
+        assert_eq!(order.seats, Some(5));
+        assert_eq!(order.purchase_date, Some(NaiveDate::from_ymd(2024, 1, 1)));
+        assert!(order.expiry.is_some());
+        assert!(order.maintenance.is_some());
+
+        let order = spec.order(1).unwrap();
+        assert_eq!(order.edition, Some(KeyEdition::Engineer));
+        assert_eq!(order.seats, None);
+        assert_eq!(order.purchase_date, None);
+        assert!(order.expiry.is_none());
+        assert!(order.maintenance.is_none());
+
+        let spec = json(r#"[{"order": 1001, "edition": "business", "seats": 10, "expiry": null}]"#);
+        let order = spec.order(0).unwrap();
+        assert_eq!(order.edition, Some(KeyEdition::Business));
+        assert_eq!(order.seats, Some(10));
+        assert!(order.expiry.is_none());
+    }
+
+    #[test]
+    fn edition() {
+        let spec = csv("order,seats\n1001,5\n");
+        assert_eq!(spec.order(0).unwrap().edition, None);
+
+        let spec = json(r#"[{"edition": "ultimate"}]"#);
+        let e = spec.order(0).err().unwrap();
+        assert!(e.starts_with("row 1, edition:"), "{e}");
+
+        let spec = csv("edition\nextreme\nultimate\n");
+        assert!(spec.order(0).is_ok());
+        assert!(spec.order(1).err().unwrap().starts_with("row 3, edition:"));
+    }
+
+    #[test]
+    fn seats() {
+        for seats in ["0", "798", "-1", "five", "1.5"] {
+            let spec = csv(&format!("seats\n{seats}\n"));
+            let e = spec.order(0).err().unwrap();
+            assert!(e.starts_with("row 2, seats:") && e.contains(seats), "{e}");
+        }
+
+        let spec = json(r#"[{"seats": 798}]"#);
+        assert!(spec.order(0).is_err());
+
+        let spec = csv(&format!("seats\n{}\n{}\n", SEATS_RANGE.start(), SEATS_RANGE.end()));
+        assert_eq!(spec.order(0).unwrap().seats, Some(*SEATS_RANGE.start()));
+        assert_eq!(spec.order(1).unwrap().seats, Some(*SEATS_RANGE.end()));
+    }
+
+    #[test]
+    fn lengths() {
+        for column in ["expiry", "maintenance"] {
+            for length in ["abc", "10x", "-5d"] {
+                let spec = csv(&format!("{column}\n{length}\n"));
+                let e = spec.order(0).err().unwrap();
+                assert!(e.starts_with(&format!("row 2, {column}:")), "{e}");
+            }
+        }
+
+        let spec = csv("purchase_date\n2024-13-01\n");
+        assert!(spec.order(0).err().unwrap().starts_with("row 2, purchase_date:"));
+    }
+
+    #[test]
+    fn write() {
+        let spec = csv("order,edition\n1001,extreme\n");
+        let mut out = Vec::new();
+        spec.write(&["KEY".to_string()], &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "order,edition,key\n1001,extreme,KEY\n");
+
+        let spec = json(r#"[{"order": 1001}]"#);
+        let mut out = Vec::new();
+        spec.write(&["KEY".to_string()], &mut out).unwrap();
+        let rows: Vec<Map<String, Value>> = serde_json::from_slice(&out).unwrap();
+        assert_eq!(rows[0]["order"], 1001);
+        assert_eq!(rows[0]["key"], "KEY");
+    }
+}
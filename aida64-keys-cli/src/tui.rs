@@ -0,0 +1,278 @@
+//! Keyboard driven interface with panes for the license parameters, the generated keys and an
+//! inspector, for terminals without a display where the GUI can't run
+
+use std::io;
+
+use aida64_keys_lib::{KeyEdition, License, MaintenancePeriod, SEATS_RANGE};
+use chrono::Duration;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use strum::IntoEnumIterator;
+
+/// Days the expiry and maintenance change by per key press
+const DAYS_STEP: i64 = 30;
+
+const FIELDS: [&str; 4] = ["edition", "seats", "expiry", "maintenance"];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Parameters,
+    Keys,
+    Inspector,
+}
+
+struct App {
+    pane: Pane,
+    field: usize,
+    edition: KeyEdition,
+    seats: i32,
+    /// Days until the license expires, never when `None`
+    expiry: Option<i64>,
+    maintenance: i64,
+    keys: Vec<String>,
+    selected: ListState,
+    /// Key shown in the inspector, typed or picked from the generated keys
+    input: String,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        App {
+            pane: Pane::Parameters,
+            field: 0,
+            edition: KeyEdition::Extreme,
+            seats: 1,
+            expiry: None,
+            maintenance: MaintenancePeriod::max().num_days(),
+            keys: Vec::new(),
+            selected: ListState::default(),
+            input: String::new(),
+        }
+    }
+}
+
+pub fn run() -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = App::default().run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !self.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Applies a key press, returns false when the interface should close
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match (self.pane, key.code) {
+            (_, KeyCode::Esc) => return false,
+            (_, KeyCode::Char('c')) if ctrl => return false,
+            (_, KeyCode::Tab) => self.pane = self.pane.next(),
+            (_, KeyCode::BackTab) => self.pane = self.pane.next().next(),
+
+            (Pane::Inspector, KeyCode::Char(c)) => self.input.push(c),
+            (Pane::Inspector, KeyCode::Backspace) => {
+                self.input.pop();
+            },
+
+            (_, KeyCode::Char('q')) => return false,
+            // ? INFO: the inspector keeps the key being typed, generating would replace it
+            (Pane::Parameters | Pane::Keys, KeyCode::Char('g') | KeyCode::Enter) => self.generate(),
+
+            (Pane::Parameters, KeyCode::Up) => self.field = self.field.saturating_sub(1),
+            (Pane::Parameters, KeyCode::Down) => {
+                self.field = (self.field + 1).min(FIELDS.len() - 1)
+            },
+            (Pane::Parameters, KeyCode::Left) => self.adjust(-1),
+            (Pane::Parameters, KeyCode::Right) => self.adjust(1),
+
+            (Pane::Keys, KeyCode::Up) => self.selected.select_previous(),
+            (Pane::Keys, KeyCode::Down) => self.selected.select_next(),
+            _ => {},
+        }
+
+        if self.pane == Pane::Keys {
+            if let Some(key) = self.selected.selected().and_then(|idx| self.keys.get(idx)) {
+                self.input.clone_from(key);
+            }
+        }
+
+        true
+    }
+
+    /// Steps the selected parameter up or down
+    fn adjust(&mut self, direction: i32) {
+        let days = DAYS_STEP * direction as i64;
+        let max_days = MaintenancePeriod::max().num_days();
+
+        match self.field {
+            0 => {
+                let editions = KeyEdition::iter().collect::<Vec<_>>();
+                let idx = editions.iter().position(|&edition| edition == self.edition).unwrap();
+                let len = editions.len() as i32;
+                self.edition = editions[(idx as i32 + direction).rem_euclid(len) as usize];
+            },
+            1 => {
+                self.seats =
+                    (self.seats + direction).clamp(*SEATS_RANGE.start(), *SEATS_RANGE.end())
+            },
+            // ? INFO: stepping below one day turns the expiry off, stepping up from never starts
+            // at one step
+            2 => {
+                self.expiry = match self.expiry.unwrap_or(0) + days {
+                    ..=0 => None,
+                    expiry => Some(expiry.min(max_days)),
+                }
+            },
+            _ => self.maintenance = (self.maintenance + days).clamp(1, max_days),
+        }
+    }
+
+    fn license(&self) -> License {
+        License::new(self.edition)
+            .with_seats(self.seats)
+            .with_license_expiry(self.expiry.map(Duration::days))
+            .with_maintenance_expiry(Duration::days(self.maintenance))
+    }
+
+    fn generate(&mut self) {
+        let key = self.license().generate_string(true);
+
+        self.input.clone_from(&key);
+        self.keys.push(key);
+        self.selected.select(Some(self.keys.len() - 1));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [body, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [parameters, keys, inspector] = Layout::horizontal([
+            Constraint::Length(34),
+            Constraint::Length(33),
+            Constraint::Min(30),
+        ])
+        .areas(body);
+
+        self.draw_parameters(frame, parameters);
+        self.draw_keys(frame, keys);
+        self.draw_inspector(frame, inspector);
+
+        let text = match self.pane {
+            Pane::Parameters => "↑↓ field  ←→ change  g/enter generate  tab next pane  q quit",
+            Pane::Keys => "↑↓ select  g/enter generate  tab next pane  q quit",
+            Pane::Inspector => "type a key to decode it  tab next pane  esc quit",
+        };
+        frame.render_widget(Line::from(text).dim(), help);
+    }
+
+    fn block(&self, pane: Pane, title: &str) -> Block<'static> {
+        let block = Block::bordered().title(format!(" {title} "));
+
+        match self.pane == pane {
+            true => block.border_style(Style::new().fg(Color::Yellow)),
+            false => block,
+        }
+    }
+
+    fn draw_parameters(&self, frame: &mut Frame, area: Rect) {
+        let expiry = match self.expiry {
+            Some(days) => format!("{days} days"),
+            None => "never".to_string(),
+        };
+        let values = [
+            self.edition.to_string(),
+            self.seats.to_string(),
+            expiry,
+            format!("{} days", self.maintenance),
+        ];
+
+        let lines = FIELDS.iter().zip(values).enumerate().map(|(idx, (field, value))| {
+            let line = Line::from(format!("{field:<12} {value}"));
+            match self.pane == Pane::Parameters && idx == self.field {
+                true => line.add_modifier(Modifier::REVERSED),
+                false => line,
+            }
+        });
+
+        let paragraph = Paragraph::new(lines.collect::<Vec<_>>())
+            .block(self.block(Pane::Parameters, "parameters"));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_keys(&mut self, frame: &mut Frame, area: Rect) {
+        let list = List::new(self.keys.iter().map(String::as_str))
+            .block(self.block(Pane::Keys, "keys"))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(list, area, &mut self.selected);
+    }
+
+    fn draw_inspector(&self, frame: &mut Frame, area: Rect) {
+        let cursor = match self.pane {
+            Pane::Inspector => "_",
+            _ => "",
+        };
+        let mut lines = vec![Line::from(format!("key: {}{cursor}", self.input)), Line::default()];
+
+        match License::from_key(&self.input) {
+            _ if self.input.is_empty() => {
+                lines.push(Line::from("generate, select or type a key").dim())
+            },
+            Ok(license) => {
+                let expiry = match license.expiry_date() {
+                    Some(date) => date.to_string(),
+                    None => "never".to_string(),
+                };
+                let valid = match license.is_valid_key() {
+                    true => Span::from("yes").green(),
+                    false => Span::from("no").red(),
+                };
+
+                lines.extend([
+                    Line::from(license.summary()),
+                    Line::default(),
+                    Line::from(format!("edition:            {}", license.edition())),
+                    Line::from(format!("seats:              {}", license.seats())),
+                    Line::from(format!("purchase date:      {}", license.purchase_date())),
+                    Line::from(format!("expiry:             {expiry}")),
+                    Line::from(format!(
+                        "maintenance expiry: {}",
+                        license.maintenance_expiry_date()
+                    )),
+                    Line::from(vec![Span::from("valid:              "), valid]),
+                ]);
+            },
+            Err(e) => lines.push(Line::from(format!("error: {e}")).red()),
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(self.block(Pane::Inspector, "inspector"));
+        frame.render_widget(paragraph, area);
+    }
+}
+
+impl Pane {
+    fn next(self) -> Pane {
+        match self {
+            Pane::Parameters => Pane::Keys,
+            Pane::Keys => Pane::Inspector,
+            Pane::Inspector => Pane::Parameters,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+#ifndef AIDA64_KEYS_H
+#define AIDA64_KEYS_H
+
+/* Generated by cbindgen from aida64-keys-ffi/src/lib.rs, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of every call, `AIDA64_STATUS_OK` on success
+typedef enum Aida64Status {
+  AIDA64_STATUS_OK = 0,
+  // A required pointer argument was null
+  AIDA64_STATUS_NULL_POINTER,
+  // A string argument was not valid UTF-8
+  AIDA64_STATUS_INVALID_UTF8,
+  AIDA64_STATUS_INVALID_LENGTH,
+  AIDA64_STATUS_INVALID_CHARACTER,
+  AIDA64_STATUS_INVALID_CHECKSUM,
+  AIDA64_STATUS_UNKNOWN_EDITION,
+  AIDA64_STATUS_INVALID_DATE,
+  AIDA64_STATUS_OUT_OF_RANGE,
+  // The key decodes fine but the license is expired or has out of range fields
+  AIDA64_STATUS_INVALID_LICENSE,
+} Aida64Status;
+
+// License fields of a key, dates are split into year, month and day
+typedef struct Aida64License {
+  // 0 = business, 1 = extreme, 2 = engineer, 3 = network audit
+  int32_t edition;
+  int32_t seats;
+  int32_t purchase_year;
+  uint32_t purchase_month;
+  uint32_t purchase_day;
+  // Days after the purchase date the license expires, 0 if it never does
+  int32_t expiry_days;
+  // Days after the purchase date the maintenance expires
+  int32_t maintenance_days;
+} Aida64License;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Generates a key for `license`, with dashes between the groups when `separators` is true.
+//
+// On success `*out_key` is set to a string owned by the caller that has to be released with
+// [`aida64_free_string`], on failure it is set to null. Out of range fields are rejected instead
+// of clamped.
+//
+// # Safety
+//
+// `license` has to point to a valid [`Aida64License`] and `out_key` to writable memory for a
+// pointer
+enum Aida64Status aida64_generate(const struct Aida64License *license,
+                                  bool separators,
+                                  char **out_key);
+
+// Decodes `key` into `*out_license` without checking whether the license is valid, use
+// [`aida64_validate`] for that. `*out_license` is left untouched on failure.
+//
+// # Safety
+//
+// `key` has to point to a nul terminated string and `out_license` to writable memory for an
+// [`Aida64License`]
+enum Aida64Status aida64_parse(const char *key, struct Aida64License *out_license);
+
+// Checks that `key` decodes to a valid, unexpired license
+//
+// # Safety
+//
+// `key` has to point to a nul terminated string
+enum Aida64Status aida64_validate(const char *key);
+
+// Releases a string returned by the library, passing null is a no-op
+//
+// # Safety
+//
+// `s` has to be null or a string returned by this library that was not freed before
+void aida64_free_string(char *s);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* AIDA64_KEYS_H */
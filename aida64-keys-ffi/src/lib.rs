@@ -0,0 +1,259 @@
+//! C interface to the key generator, the header is generated into `include/aida64_keys.h` on
+//! every build.
+//!
+//! Ownership rules:
+//! - strings passed in are borrowed for the duration of the call and never freed by the library
+//! - strings returned by the library are owned by the caller and have to be released with
+//!   [`aida64_free_string`], not with `free`
+//! - [`Aida64License`] is a plain value, the caller owns it and the library only reads or fills it
+
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+use aida64_keys_lib::{KeyEdition, KeyError, License};
+use chrono::{Datelike, Duration, NaiveDate};
+
+/// Result of every call, `AIDA64_STATUS_OK` on success
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Aida64Status {
+    Ok = 0,
+    /// A required pointer argument was null
+    NullPointer,
+    /// A string argument was not valid UTF-8
+    InvalidUtf8,
+    InvalidLength,
+    InvalidCharacter,
+    InvalidChecksum,
+    UnknownEdition,
+    InvalidDate,
+    OutOfRange,
+    /// The key decodes fine but the license is expired or has out of range fields
+    InvalidLicense,
+}
+
+impl From<KeyError> for Aida64Status {
+    fn from(e: KeyError) -> Aida64Status {
+        match e {
+            KeyError::InvalidLength { .. } => Aida64Status::InvalidLength,
+            KeyError::InvalidCharacter { .. } => Aida64Status::InvalidCharacter,
+            KeyError::InvalidChecksum { .. } => Aida64Status::InvalidChecksum,
+            KeyError::UnknownEdition { .. } => Aida64Status::UnknownEdition,
+            KeyError::InvalidDate => Aida64Status::InvalidDate,
+            KeyError::DateOutOfRange { .. } | KeyError::ValueOutOfRange { .. } => {
+                Aida64Status::OutOfRange
+            },
+            KeyError::FieldMismatch { .. } | KeyError::InvalidLicense => {
+                Aida64Status::InvalidLicense
+            },
+        }
+    }
+}
+
+/// License fields of a key, dates are split into year, month and day
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Aida64License {
+    /// 0 = business, 1 = extreme, 2 = engineer, 3 = network audit
+    pub edition: i32,
+    pub seats: i32,
+    pub purchase_year: i32,
+    pub purchase_month: u32,
+    pub purchase_day: u32,
+    /// Days after the purchase date the license expires, 0 if it never does
+    pub expiry_days: i32,
+    /// Days after the purchase date the maintenance expires
+    pub maintenance_days: i32,
+}
+
+impl Aida64License {
+    fn to_license(self) -> Result<License, KeyError> {
+        let purchase_date =
+            NaiveDate::from_ymd_opt(self.purchase_year, self.purchase_month, self.purchase_day)
+                .ok_or(KeyError::InvalidDate)?;
+
+        let license = License::new(KeyEdition::try_from(self.edition)?)
+            .try_with_seats(self.seats)?
+            .try_with_purchase_date(purchase_date)?
+            .try_with_maintenance_expiry(Duration::days(self.maintenance_days.into()))?;
+
+        match self.expiry_days {
+            0 => Ok(license),
+            days => license.with_expiry_date(purchase_date + Duration::days(days.into())),
+        }
+    }
+
+    fn from_license(license: &License) -> Aida64License {
+        Aida64License {
+            edition: license.edition() as i32,
+            seats: license.seats(),
+            purchase_year: license.purchase_date().year(),
+            purchase_month: license.purchase_date().month(),
+            purchase_day: license.purchase_date().day(),
+            expiry_days: license.expiry().map_or(0, |expiry| expiry.num_days() as i32),
+            maintenance_days: license.maintenance_expiry().num_days() as i32,
+        }
+    }
+}
+
+/// Borrows a C string argument as `&str`
+///
+/// # Safety
+///
+/// `s` has to be null or point to a nul terminated string that outlives the returned reference
+unsafe fn borrow_str<'a>(s: *const c_char) -> Result<&'a str, Aida64Status> {
+    if s.is_null() {
+        return Err(Aida64Status::NullPointer);
+    }
+
+    CStr::from_ptr(s).to_str().map_err(|_| Aida64Status::InvalidUtf8)
+}
+
+/// Generates a key for `license`, with dashes between the groups when `separators` is true.
+///
+/// On success `*out_key` is set to a string owned by the caller that has to be released with
+/// [`aida64_free_string`], on failure it is set to null. Out of range fields are rejected instead
+/// of clamped.
+///
+/// # Safety
+///
+/// `license` has to point to a valid [`Aida64License`] and `out_key` to writable memory for a
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn aida64_generate(
+    license: *const Aida64License,
+    separators: bool,
+    out_key: *mut *mut c_char,
+) -> Aida64Status {
+    if license.is_null() || out_key.is_null() {
+        return Aida64Status::NullPointer;
+    }
+
+    *out_key = ptr::null_mut();
+
+    let license = match (*license).to_license() {
+        Ok(license) => license,
+        Err(e) => return e.into(),
+    };
+
+    // ? INFO: keys only ever contain the key alphabet and dashes, so there is no interior nul
+    *out_key = CString::new(license.generate_string(separators)).unwrap().into_raw();
+    Aida64Status::Ok
+}
+
+/// Decodes `key` into `*out_license` without checking whether the license is valid, use
+/// [`aida64_validate`] for that. `*out_license` is left untouched on failure.
+///
+/// # Safety
+///
+/// `key` has to point to a nul terminated string and `out_license` to writable memory for an
+/// [`Aida64License`]
+#[no_mangle]
+pub unsafe extern "C" fn aida64_parse(
+    key: *const c_char,
+    out_license: *mut Aida64License,
+) -> Aida64Status {
+    if out_license.is_null() {
+        return Aida64Status::NullPointer;
+    }
+
+    let key = match borrow_str(key) {
+        Ok(key) => key,
+        Err(status) => return status,
+    };
+
+    match License::from_key(key) {
+        Ok(license) => {
+            *out_license = Aida64License::from_license(&license);
+            Aida64Status::Ok
+        },
+        Err(e) => e.into(),
+    }
+}
+
+/// Checks that `key` decodes to a valid, unexpired license
+///
+/// # Safety
+///
+/// `key` has to point to a nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn aida64_validate(key: *const c_char) -> Aida64Status {
+    let key = match borrow_str(key) {
+        Ok(key) => key,
+        Err(status) => return status,
+    };
+
+    match aida64_keys_lib::bulk::validate_key(key) {
+        Ok(_) => Aida64Status::Ok,
+        Err(e) => e.into(),
+    }
+}
+
+/// Releases a string returned by the library, passing null is a no-op
+///
+/// # Safety
+///
+/// `s` has to be null or a string returned by this library that was not freed before
+#[no_mangle]
+pub unsafe extern "C" fn aida64_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let license = Aida64License {
+            edition: KeyEdition::Engineer as i32,
+            seats: 25,
+            purchase_year: 2024,
+            purchase_month: 2,
+            purchase_day: 29,
+            expiry_days: 3000,
+            maintenance_days: 730,
+        };
+
+        unsafe {
+            let mut key = ptr::null_mut();
+            assert_eq!(aida64_generate(&license, true, &mut key), Aida64Status::Ok);
+            assert!(!key.is_null());
+
+            let mut parsed = Aida64License { seats: 0, ..license };
+            assert_eq!(aida64_parse(key, &mut parsed), Aida64Status::Ok);
+            assert_eq!(parsed, license);
+            assert_eq!(aida64_validate(key), Aida64Status::Ok);
+
+            aida64_free_string(key);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let license = Aida64License {
+            edition: 7,
+            seats: 1,
+            purchase_year: 2024,
+            purchase_month: 1,
+            purchase_day: 1,
+            expiry_days: 0,
+            maintenance_days: 365,
+        };
+
+        unsafe {
+            let mut key = ptr::null_mut();
+            assert_eq!(aida64_generate(&license, true, &mut key), Aida64Status::UnknownEdition);
+            assert!(key.is_null());
+
+            let license = Aida64License { edition: 0, seats: 0, ..license };
+            assert_eq!(aida64_generate(&license, true, &mut key), Aida64Status::OutOfRange);
+            assert_eq!(aida64_generate(ptr::null(), true, &mut key), Aida64Status::NullPointer);
+
+            assert_eq!(aida64_validate(ptr::null()), Aida64Status::NullPointer);
+            assert_eq!(aida64_validate(c"ABC".as_ptr()), Aida64Status::InvalidLength);
+        }
+    }
+}
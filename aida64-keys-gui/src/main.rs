@@ -1,54 +1,195 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::collections::HashSet;
-use std::ops::Sub;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
 
-use aida64_keys_lib::{KeyEdition, License};
-use chrono::{Date, Duration, TimeZone, Utc};
+use aida64_keys_lib::qr::KeyQr;
+use aida64_keys_lib::{
+    bulk, parse_base_pair, random_base_pair, DateStep, KeyEdition, KeyError, License,
+    LicenseRecord, SEATS_RANGE, UNK1_RANGE, UNK2_RANGE, UNK3_RANGE,
+};
+use chrono::{Date, Duration, NaiveDate, TimeZone, Utc};
 use clipboard::{ClipboardContext, ClipboardProvider};
 use eframe::egui::{self, Layout};
 use eframe::emath::Align;
 use eframe::epaint::Vec2;
 use egui_datepicker::DatePicker;
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 
-struct NotePopup {
+const MAX_LICENSE_COUNT: usize = 100_000;
+const MIN_PANEL_WIDTH: f32 = 240.0;
+const MIN_UI_SCALE: f32 = 0.75;
+const MAX_UI_SCALE: f32 = 2.0;
+const CLIPBOARD_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+/// Pixels per QR code module in the exported PNG, the preview shows it at half the size
+const QR_SCALE: usize = 8;
+const QR_QUIET_ZONE: usize = 4;
+/// Distinct base pairs, the most unique keys a license with fixed components can produce
+const MAX_KEYS_PER_COMPONENTS: usize = 34 * 34;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Severity {
+    Info,
+    Success,
+    Error,
+}
+
+impl Severity {
+    fn color(self) -> egui::Color32 {
+        match self {
+            Severity::Info => egui::Color32::LIGHT_BLUE,
+            Severity::Success => egui::Color32::GREEN,
+            Severity::Error => egui::Color32::RED,
+        }
+    }
+
+    /// Errors stay twice as long, they usually need to be read
+    fn duration(self) -> std::time::Duration {
+        match self {
+            Severity::Error => std::time::Duration::from_secs(8),
+            _ => std::time::Duration::from_secs(4),
+        }
+    }
+}
+
+struct Toast {
+    severity: Severity,
     text: String,
+    shown: Instant,
 }
 
-impl NotePopup {
-    fn new(text: String) -> NotePopup {
-        Self { text }
+impl Toast {
+    fn remaining(&self) -> std::time::Duration {
+        self.severity.duration().saturating_sub(self.shown.elapsed())
     }
+}
 
-    fn show(&self, ctx: &egui::Context) -> bool {
-        let mut wants_close = false;
+/// Non-blocking notifications stacked in the bottom right corner, dismissed after a while or
+/// when clicked
+#[derive(Default)]
+struct Toasts(Vec<Toast>);
 
-        egui::Window::new("note_window")
-            .default_size(egui::Vec2 { x: 250.0, y: 50.0 })
-            .resizable(false)
-            .title_bar(false)
-            .anchor(egui::Align2::CENTER_CENTER, Vec2::default())
-            .show(ctx, |ui| {
-                let layout = Layout::top_down(Align::Center).with_cross_justify(true);
-                ui.with_layout(layout, |ui| {
-                    ui.label(&self.text);
-                    ui.add_space(2.5);
-                    if ui.button("OK").clicked() {
-                        wants_close |= true;
-                    }
-                });
-            });
+impl Toasts {
+    fn push<T: Into<String>>(&mut self, severity: Severity, text: T) {
+        self.0.push(Toast { severity, text: text.into(), shown: Instant::now() });
+    }
+
+    fn show(&mut self, ctx: &egui::Context) {
+        self.0.retain(|toast| !toast.remaining().is_zero());
+
+        let mut dismissed = None;
+        egui::Area::new("toasts").anchor(egui::Align2::RIGHT_BOTTOM, Vec2::new(-8.0, -8.0)).show(
+            ctx,
+            |ui| {
+                for (idx, toast) in self.0.iter().enumerate() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        let text = egui::RichText::new(&toast.text).color(toast.severity.color());
+                        if ui.add(egui::Label::new(text).sense(egui::Sense::click())).clicked() {
+                            dismissed = Some(idx);
+                        }
+                    });
+                }
+            },
+        );
+
+        if let Some(idx) = dismissed {
+            self.0.remove(idx);
+        }
+
+        if let Some(remaining) = self.0.iter().map(Toast::remaining).min() {
+            ctx.request_repaint_after(remaining);
+        }
+    }
+}
+
+/// Keys being generated on a worker thread, streamed back to the list as they arrive
+struct GenerationJob {
+    receiver: Receiver<(String, License)>,
+    cancel: Arc<AtomicBool>,
+    total: usize,
+}
+
+impl GenerationJob {
+    fn spawn<I>(total: usize, licenses: I) -> GenerationJob
+    where
+        I: Iterator<Item = (String, License)> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let worker_cancel = cancel.clone();
+        thread::spawn(move || {
+            for entry in licenses.take(total) {
+                if worker_cancel.load(Ordering::Relaxed) || sender.send(entry).is_err() {
+                    break;
+                }
+            }
+        });
+
+        GenerationJob { receiver, cancel, total }
+    }
+}
 
-        wants_close
+impl Drop for GenerationJob {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
     }
 }
 
+/// Generator configuration restored on the next start, eframe restores the window itself
+#[derive(Serialize, Deserialize)]
+struct Settings {
+    edition: KeyEdition,
+    seats: i32,
+    count: usize,
+    purchase: NaiveDate,
+    expire: NaiveDate,
+    expire_never: bool,
+    maintenance: NaiveDate,
+    #[serde(default)]
+    theme: ThemePreference,
+    #[serde(default = "default_ui_scale")]
+    ui_scale: f32,
+}
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum ThemePreference {
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Tab {
+    Generate,
+    Inspect,
+}
+
 struct App {
-    note: Option<NotePopup>,
+    toasts: Toasts,
+    tab: Tab,
+    theme: ThemePreference,
+    ui_scale: f32,
+    /// Dark mode and scale last applied to the context, to only restyle on changes
+    applied_style: Option<(bool, f32)>,
+    inspect_key: String,
 
-    licenses: HashSet<String>,
+    licenses: Vec<(String, License)>,
     license_count: usize,
+    generation: Option<GenerationJob>,
 
     license_edition: KeyEdition,
     license_seats: i32,
@@ -57,18 +198,39 @@ struct App {
     license_expire_never: bool,
     license_maintenance: Date<Utc>,
 
-    selected_license: Option<usize>,
+    sweep_enabled: bool,
+    sweep_end: Date<Utc>,
+    sweep_step: DateStep,
+
+    advanced_enabled: bool,
+    advanced_components: [i32; 3],
+    advanced_base_fixed: bool,
+    advanced_base: String,
+
+    selected_licenses: BTreeSet<usize>,
+    selection_anchor: Option<usize>,
+    /// QR code of the selected key and the key it was rendered for
+    qr_texture: Option<(String, egui::TextureHandle)>,
 
     clipboard_provider: ClipboardContext,
+    clipboard_watch: bool,
+    clipboard_seen: String,
+    clipboard_polled: Option<Instant>,
 }
 
 impl Default for App {
     fn default() -> Self {
         Self {
-            note: None,
+            toasts: Toasts::default(),
+            tab: Tab::Generate,
+            theme: ThemePreference::System,
+            ui_scale: default_ui_scale(),
+            applied_style: None,
+            inspect_key: String::new(),
 
-            licenses: HashSet::new(),
+            licenses: Vec::new(),
             license_count: 1,
+            generation: None,
 
             license_edition: KeyEdition::Extreme,
             license_seats: 1,
@@ -77,54 +239,166 @@ impl Default for App {
             license_expire_never: true,
             license_maintenance: Utc::today() + Duration::days(3658),
 
-            selected_license: None,
+            sweep_enabled: false,
+            sweep_end: Utc::today() + Duration::days(365),
+            sweep_step: DateStep::Month,
+
+            advanced_enabled: false,
+            advanced_components: License::new(KeyEdition::Extreme).components(),
+            advanced_base_fixed: false,
+            advanced_base: String::from_utf8_lossy(&random_base_pair()).into_owned(),
+
+            selected_licenses: BTreeSet::new(),
+            selection_anchor: None,
+            qr_texture: None,
 
             clipboard_provider: ClipboardProvider::new().expect("Failed to get clipboard provider"),
+            clipboard_watch: false,
+            clipboard_seen: String::new(),
+            clipboard_polled: None,
         }
     }
 }
 
-impl eframe::App for App {
-    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
-        if let Some(note) = &self.note {
-            note.show(ctx).then(|| self.note = None);
+impl App {
+    fn new(cc: &eframe::CreationContext) -> App {
+        let mut app = App::default();
+
+        if let Some(settings) =
+            cc.storage.and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+        {
+            app.apply_settings(settings);
         }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            if self.note.is_some() {
-                ui.set_enabled(false);
-            }
+        app
+    }
 
-            ui.columns(2, |columns| {
-                columns[0].group(|ui| {
-                    let available_size = ui.available_size();
+    fn settings(&self) -> Settings {
+        Settings {
+            edition: self.license_edition,
+            seats: self.license_seats,
+            count: self.license_count,
+            purchase: self.license_purchase.naive_utc(),
+            expire: self.license_expire.naive_utc(),
+            expire_never: self.license_expire_never,
+            maintenance: self.license_maintenance.naive_utc(),
+            theme: self.theme,
+            ui_scale: self.ui_scale,
+        }
+    }
 
-                    ui.set_max_size(available_size);
-                    ui.set_min_size(available_size);
+    fn apply_settings(&mut self, settings: Settings) {
+        self.license_edition = settings.edition;
+        self.license_seats = settings.seats;
+        self.license_count = settings.count;
+        self.license_purchase = Utc.from_utc_date(&settings.purchase);
+        self.license_expire = Utc.from_utc_date(&settings.expire);
+        self.license_expire_never = settings.expire_never;
+        self.license_maintenance = Utc.from_utc_date(&settings.maintenance);
+        self.theme = settings.theme;
+        self.ui_scale = settings.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+    }
 
-                    ui.columns(2, |columns| {
-                        columns[0].vertical_centered_justified(|ui| {
-                            if ui.button("Generate").clicked() {
-                                self.licenses.clear();
-                                self.selected_license = None;
-
-                                while self.licenses.len() < self.license_count {
-                                    let mut license = License::new(self.license_edition)
-                                        .with_seats(self.license_seats)
-                                        .with_purchase_date(self.license_purchase)
-                                        .with_maintenance_expiry(
-                                            self.license_maintenance
-                                                .sub(self.license_purchase)
+    fn build_license(&self) -> Result<License, KeyError> {
+        let mut license = License::new(self.license_edition)
+            .with_seats(self.license_seats)
+            .with_purchase_date(self.license_purchase)
+            .with_maintenance_expiry_date(self.license_maintenance)?;
 
-                                        );
+        if !self.license_expire_never {
+            license = license.with_expiry_date(self.license_expire)?;
+        }
 
-                                    if !self.license_expire_never {
-                                        license = license.with_license_expiry(Some(self.license_expire
-                                            .sub(self.license_purchase)
-                                        ));
-                                    }
+        if self.advanced_enabled {
+            let base = match self.advanced_base_fixed {
+                true => Some(parse_base_pair(self.advanced_base.trim().to_ascii_uppercase())?),
+                false => None,
+            };
+            license = license.with_components(self.advanced_components)?.with_base_pair(base)?;
+        }
 
-                                    self.licenses.insert(license.generate_string(true));
+        Ok(license)
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.advanced_enabled && self.advanced_base_fixed
+    }
+
+    fn generate_licenses(&mut self) -> Result<(), KeyError> {
+        let license = self.build_license()?;
+
+        self.licenses.clear();
+        self.selected_licenses.clear();
+        self.selection_anchor = None;
+
+        if self.sweep_enabled {
+            let range = self.license_purchase.naive_utc()..=self.sweep_end.naive_utc();
+            let sweep = license.sweep_dates(range, self.sweep_step).collect::<Vec<_>>();
+
+            let total = sweep.len();
+            let licenses =
+                sweep.into_iter().map(|license| (license.generate_string(true), license));
+            self.generation = Some(GenerationJob::spawn(total, licenses));
+            return Ok(());
+        }
+
+        // ? INFO: unique_keys only draws new components once the base pairs run out, capping
+        // the count keeps the components fixed in the advanced settings
+        let total = match (self.is_deterministic(), self.advanced_enabled) {
+            (true, _) => 1,
+            (false, false) => self.license_count,
+            (false, true) => self.license_count.min(MAX_KEYS_PER_COMPONENTS),
+        };
+
+        let licenses = license.unique_keys().map(|key| {
+            let license = License::from_key(&key).expect("generated keys always parse");
+            (key, license)
+        });
+
+        self.generation = Some(GenerationJob::spawn(total, licenses));
+        Ok(())
+    }
+
+    /// Moves keys finished by the worker into the list, keeps repainting while it runs
+    fn poll_generation(&mut self, ctx: &egui::Context) {
+        let job = match &self.generation {
+            Some(job) => job,
+            None => return,
+        };
+
+        let finished = loop {
+            match job.receiver.try_recv() {
+                Ok(entry) => self.licenses.push(entry),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+
+        match finished {
+            true => {
+                self.generation = None;
+                self.toasts.push(Severity::Info, format!("Generated {} keys", self.licenses.len()));
+            },
+            false => ctx.request_repaint(),
+        }
+    }
+
+    fn generator_ui(&mut self, ui: &mut egui::Ui) {
+        let max_width = (ui.available_width() - MIN_PANEL_WIDTH).max(MIN_PANEL_WIDTH);
+        egui::SidePanel::left("generator_panel")
+            .resizable(true)
+            .width_range(MIN_PANEL_WIDTH..=max_width)
+            .show_inside(ui, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.columns(2, |columns| {
+                        columns[0].vertical_centered_justified(|ui| {
+                            let idle = self.generation.is_none();
+                            if ui.add_enabled(idle, egui::Button::new("Generate")).clicked() {
+                                if let Err(e) = self.generate_licenses() {
+                                    self.toasts.push(
+                                        Severity::Error,
+                                        format!("Invalid license parameters: {e}"),
+                                    );
                                 }
                             }
                         });
@@ -134,27 +408,30 @@ impl eframe::App for App {
                                 .width(ui.available_width() - 8.0)
                                 .selected_text(self.license_edition.to_string())
                                 .show_ui(ui, |ui| {
-                                    KeyEdition::iter().for_each(|edition| {
+                                    KeyEdition::all().into_iter().for_each(|edition| {
                                         ui.selectable_value(
                                             &mut self.license_edition,
                                             edition,
                                             edition.to_string(),
-                                        );
+                                        )
+                                        .on_hover_text(edition.description());
                                     });
                                 });
                         });
                     });
 
                     ui.separator();
-                    ui.add(
-                        egui::Slider::new(&mut self.license_count, 1..=500)
+                    ui.add_enabled(
+                        !self.sweep_enabled && !self.is_deterministic(),
+                        egui::Slider::new(&mut self.license_count, 1..=MAX_LICENSE_COUNT)
+                            .logarithmic(true)
                             .text("License count")
                             .show_value(true),
                     )
                     .on_hover_text("Number of licenses to generate");
 
                     ui.add(
-                        egui::Slider::new(&mut self.license_seats, 1..=797)
+                        egui::Slider::new(&mut self.license_seats, SEATS_RANGE)
                             .text("Seats")
                             .show_value(true),
                     );
@@ -163,7 +440,7 @@ impl eframe::App for App {
                         ui.add(
                             DatePicker::new("license_purchase_date", &mut self.license_purchase)
                                 .min_date(Utc.ymd(2004, 1, 1))
-                                .max_date(Utc.ymd(2099, 12, 31)),
+                                .max_date(Utc.ymd(2034, 12, 31)),
                         );
                         ui.label("Purchase Date");
                     });
@@ -198,46 +475,569 @@ impl eframe::App for App {
                         );
                         ui.label("Maintenance Expire Date");
                     });
-                });
 
-                columns[1].group(|ui| {
-                    let available_size = ui.available_size();
-
-                    ui.set_max_size(available_size);
-                    ui.set_min_size(available_size);
-
-                    egui::ScrollArea::new([false, true]).show(ui, |ui| {
-                        self.licenses.iter().enumerate().for_each(|(idx, license)| {
-                            if ui
-                                .selectable_label(
-                                    matches!(self.selected_license, Some(sel_idx) if sel_idx == idx),
-                                    egui::RichText::new(license)
-                                        .text_style(egui::TextStyle::Monospace),
-                                )
-                                .clicked()
-                            {
-                                self.selected_license = Some(idx);
-
-                                if let Err(e) = self.clipboard_provider.set_contents(license.to_string()) {
-                                    self.note = Some(NotePopup::new(format!("Failed to set cliboard content: {e}")));
+                    self.sweep_end = self.sweep_end.clamp(self.license_purchase, max_date);
+
+                    ui.horizontal(|ui| {
+                        ui.add_enabled_ui(self.sweep_enabled, |ui| {
+                            ui.add(
+                                DatePicker::new("sweep_end_date", &mut self.sweep_end)
+                                    .min_date(self.license_purchase)
+                                    .max_date(max_date),
+                            );
+                            egui::ComboBox::from_id_source("sweep_step_combobox")
+                                .selected_text(self.sweep_step.to_string())
+                                .show_ui(ui, |ui| {
+                                    DateStep::iter().for_each(|step| {
+                                        ui.selectable_value(
+                                            &mut self.sweep_step,
+                                            step,
+                                            step.to_string(),
+                                        );
+                                    });
+                                });
+                        });
+
+                        ui.checkbox(&mut self.sweep_enabled, "Sweep Purchase Dates")
+                            .on_hover_text("Generate one license per step from the purchase date up to the sweep end date");
+                    });
+
+                    ui.collapsing("Advanced", |ui| {
+                        ui.checkbox(&mut self.advanced_enabled, "Override Random Components");
+
+                        ui.add_enabled_ui(self.advanced_enabled, |ui| {
+                            ui.horizontal(|ui| {
+                                let ranges = [UNK1_RANGE, UNK2_RANGE, UNK3_RANGE];
+                                for (value, range) in self.advanced_components.iter_mut().zip(ranges) {
+                                    ui.add(egui::DragValue::new(value).clamp_range(range));
                                 }
-                            }
+                                if ui.small_button("Randomize").clicked() {
+                                    self.advanced_components =
+                                        License::new(self.license_edition).components();
+                                }
+                                ui.label("Components");
+                            });
+
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut self.advanced_base_fixed, "");
+                                ui.add_enabled(
+                                    self.advanced_base_fixed,
+                                    egui::TextEdit::singleline(&mut self.advanced_base)
+                                        .font(egui::TextStyle::Monospace)
+                                        .desired_width(24.0),
+                                );
+                                if ui.small_button("Randomize").clicked() {
+                                    self.advanced_base =
+                                        String::from_utf8_lossy(&random_base_pair()).into_owned();
+                                }
+                                ui.label("Base Pair");
+
+                                let base = self.advanced_base.trim().to_ascii_uppercase();
+                                if let (true, Err(e)) =
+                                    (self.advanced_base_fixed, parse_base_pair(base))
+                                {
+                                    ui.colored_label(egui::Color32::RED, e.to_string());
+                                }
+                            });
                         });
                     });
                 });
             });
+
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            ui.horizontal(|ui| {
+                let selected = match self.selected_licenses.len() {
+                    1 => self.selected_licenses.first().and_then(|&idx| self.licenses.get(idx)),
+                    _ => None,
+                };
+                ui.add_enabled_ui(selected.is_some(), |ui| {
+                    if ui.button("Share…").on_hover_text("Send the selected key by mail").clicked()
+                    {
+                        if let Some((key, license)) = selected {
+                            if let Err(e) = open::that(share_mailto(key, license)) {
+                                self.toasts.push(
+                                    Severity::Error,
+                                    format!("Failed to open mail client: {e}"),
+                                );
+                            }
+                        }
+                    }
+                });
+
+                ui.add_enabled_ui(!self.licenses.is_empty(), |ui| {
+                    if ui
+                        .button("Export…")
+                        .on_hover_text("Save all keys as TXT, CSV or JSON")
+                        .clicked()
+                    {
+                        let path = rfd::FileDialog::new()
+                            .add_filter("Text", &["txt"])
+                            .add_filter("CSV", &["csv"])
+                            .add_filter("JSON", &["json"])
+                            .set_file_name("licenses.txt")
+                            .save_file();
+
+                        if let Some(path) = path {
+                            match self.export_licenses(&path) {
+                                Ok(()) => self.toasts.push(
+                                    Severity::Success,
+                                    format!(
+                                        "Exported {} keys to {}",
+                                        self.licenses.len(),
+                                        path.display()
+                                    ),
+                                ),
+                                Err(e) => self.toasts.push(
+                                    Severity::Error,
+                                    format!("Failed to export licenses: {e}"),
+                                ),
+                            }
+                        }
+                    }
+
+                    if ui.button("Copy All").clicked() {
+                        let keys = self.licenses.iter().map(|(key, _)| key.as_str());
+                        self.copy_to_clipboard(keys.collect::<Vec<_>>().join("\n"));
+                    }
+                });
+
+                ui.add_enabled_ui(!self.selected_licenses.is_empty(), |ui| {
+                    if ui
+                        .button("Copy Selected")
+                        .on_hover_text("Ctrl/Shift-click keys to select several")
+                        .clicked()
+                    {
+                        let keys =
+                            self.selected_licenses.iter().filter_map(|&idx| self.licenses.get(idx));
+                        let keys = keys.map(|(key, _)| key.as_str()).collect::<Vec<_>>().join("\n");
+                        self.copy_to_clipboard(keys);
+                    }
+                });
+            });
+
+            if let Some(job) = &self.generation {
+                let mut cancel = false;
+                ui.horizontal(|ui| {
+                    cancel = ui.button("Cancel").clicked();
+                    ui.add(
+                        egui::ProgressBar::new(self.licenses.len() as f32 / job.total as f32)
+                            .text(format!("{} / {}", self.licenses.len(), job.total)),
+                    );
+                });
+
+                if cancel {
+                    self.generation = None;
+                    self.toasts.push(
+                        Severity::Info,
+                        format!("Cancelled after {} keys", self.licenses.len()),
+                    );
+                }
+            }
+
+            if let (1, Some(&idx)) = (self.selected_licenses.len(), self.selected_licenses.first())
+            {
+                if let Some((key, license)) = self.licenses.get(idx) {
+                    egui::CollapsingHeader::new("Details").default_open(true).show(ui, |ui| {
+                        license_details_ui(ui, "details_grid", key, Some(license));
+                    });
+
+                    let key = key.clone();
+                    egui::CollapsingHeader::new("QR Code").show(ui, |ui| self.qr_ui(ui, &key));
+                }
+            }
+
+            let mut clicked = None;
+            egui::ScrollArea::new([false, true]).show(ui, |ui| {
+                self.licenses.iter().enumerate().for_each(|(idx, (license, _))| {
+                    if ui
+                        .selectable_label(
+                            self.selected_licenses.contains(&idx),
+                            egui::RichText::new(license).text_style(egui::TextStyle::Monospace),
+                        )
+                        .clicked()
+                    {
+                        clicked = Some(idx);
+                    }
+                });
+            });
+
+            if let Some(idx) = clicked {
+                let modifiers = ui.input().modifiers;
+                self.select_license(idx, modifiers);
+            }
+        });
+    }
+
+    /// Plain clicks select and copy a single key, ctrl toggles a key and shift selects the range
+    /// from the last plain or ctrl clicked key
+    fn select_license(&mut self, idx: usize, modifiers: egui::Modifiers) {
+        match (modifiers.shift, self.selection_anchor) {
+            (true, Some(anchor)) => {
+                self.selected_licenses = (anchor.min(idx)..=anchor.max(idx)).collect();
+            },
+            _ if modifiers.command => {
+                if !self.selected_licenses.remove(&idx) {
+                    self.selected_licenses.insert(idx);
+                }
+                self.selection_anchor = Some(idx);
+            },
+            _ => {
+                self.selected_licenses = BTreeSet::from([idx]);
+                self.selection_anchor = Some(idx);
+
+                if let Some((key, _)) = self.licenses.get(idx) {
+                    self.copy_to_clipboard(key.clone());
+                }
+            },
+        }
+    }
+
+    /// Applies the theme and scale, following the OS theme when it is reported
+    fn apply_style(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        let info = frame.info();
+        let dark = match self.theme {
+            ThemePreference::System => info.system_theme != Some(eframe::Theme::Light),
+            ThemePreference::Dark => true,
+            ThemePreference::Light => false,
+        };
+
+        if self.applied_style == Some((dark, self.ui_scale)) {
+            return;
+        }
+
+        ctx.set_visuals(match dark {
+            true => egui::Visuals::dark(),
+            false => egui::Visuals::light(),
         });
+        ctx.set_pixels_per_point(info.native_pixels_per_point.unwrap_or(1.0) * self.ui_scale);
+        self.applied_style = Some((dark, self.ui_scale));
+    }
+
+    fn settings_menu_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label("Theme");
+        ui.radio_value(&mut self.theme, ThemePreference::System, "System");
+        ui.radio_value(&mut self.theme, ThemePreference::Dark, "Dark");
+        ui.radio_value(&mut self.theme, ThemePreference::Light, "Light");
+        ui.separator();
+
+        ui.add(egui::Slider::new(&mut self.ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE).text("UI Scale"));
     }
+
+    fn copy_to_clipboard(&mut self, text: String) {
+        // ? INFO: keeps the clipboard watcher from reporting keys copied from the list
+        self.clipboard_seen = text.clone();
+
+        if let Err(e) = self.clipboard_provider.set_contents(text) {
+            self.toasts.push(Severity::Error, format!("Failed to set cliboard content: {e}"));
+        }
+    }
+
+    /// Polls the clipboard while watching is enabled and toasts the decoded fields of the first
+    /// key found in newly copied text
+    fn watch_clipboard(&mut self, ctx: &egui::Context) {
+        if !self.clipboard_watch {
+            return;
+        }
+
+        ctx.request_repaint_after(CLIPBOARD_POLL_INTERVAL);
+        if self.clipboard_polled.is_some_and(|polled| polled.elapsed() < CLIPBOARD_POLL_INTERVAL) {
+            return;
+        }
+        self.clipboard_polled = Some(Instant::now());
+
+        let contents = match self.clipboard_provider.get_contents() {
+            Ok(contents) if contents != self.clipboard_seen => contents,
+            _ => return,
+        };
+
+        if let Some(candidate) = bulk::find_keys_in_text(&contents).first() {
+            let key = candidate.text;
+            match License::from_key(key) {
+                Ok(license) => {
+                    let expiry = license.expiry_date().map(format_date);
+                    let text = format!(
+                        "{key}\n{}, {} seats, expires {}",
+                        license.edition(),
+                        license.seats(),
+                        expiry.as_deref().unwrap_or("never")
+                    );
+                    match license.is_valid_key() {
+                        true => self.toasts.push(Severity::Success, text),
+                        false => self.toasts.push(Severity::Error, format!("{text} (invalid)")),
+                    }
+                },
+                Err(e) => self.toasts.push(Severity::Error, format!("{key}\n{e}")),
+            }
+        }
+
+        self.clipboard_seen = contents;
+    }
+
+    /// QR code of `key` with a button to save it, the texture is kept until the key changes
+    fn qr_ui(&mut self, ui: &mut egui::Ui, key: &str) {
+        let texture = match &self.qr_texture {
+            Some((rendered, texture)) if rendered == key => texture.clone(),
+            _ => {
+                let (side, pixels) = match qr_pixels(key) {
+                    Ok(qr) => qr,
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::RED, format!("Invalid key: {e}"));
+                        return;
+                    },
+                };
+
+                let rgba = pixels.iter().flat_map(|&p| [p, p, p, u8::MAX]).collect::<Vec<_>>();
+                let image = egui::ColorImage::from_rgba_unmultiplied([side, side], &rgba);
+                let texture = ui.ctx().load_texture("qr", image, egui::TextureFilter::Nearest);
+
+                self.qr_texture = Some((key.to_string(), texture.clone()));
+                texture
+            },
+        };
+
+        ui.image(&texture, texture.size_vec2() / 2.0);
+
+        if ui.button("Save QR as PNG…").clicked() {
+            let path = rfd::FileDialog::new()
+                .add_filter("PNG", &["png"])
+                .set_file_name("license.png")
+                .save_file();
+
+            if let Some(path) = path {
+                match save_qr_png(key, &path) {
+                    Ok(()) => self
+                        .toasts
+                        .push(Severity::Success, format!("Saved QR code to {}", path.display())),
+                    Err(e) => {
+                        self.toasts.push(Severity::Error, format!("Failed to save QR code: {e}"))
+                    },
+                }
+            }
+        }
+    }
+
+    fn export_licenses(&self, path: &Path) -> io::Result<()> {
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+
+        let contents = match extension.to_ascii_lowercase().as_str() {
+            "csv" => {
+                let mut csv = format!("{}\n", LicenseRecord::CSV_HEADER);
+                for (key, license) in &self.licenses {
+                    csv += &LicenseRecord::new(key, license).to_csv_row();
+                    csv.push('\n');
+                }
+                csv
+            },
+            "json" => {
+                let licenses = self
+                    .licenses
+                    .iter()
+                    .map(|(key, license)| {
+                        let record = LicenseRecord::new(key, license);
+                        serde_json::json!({
+                            "key": record.key,
+                            "edition": record.edition.to_string(),
+                            "seats": record.seats,
+                            "purchase_date": format_date(record.purchase_date),
+                            "expiry_date": record.expiry_date.map(format_date),
+                            "maintenance_date": format_date(record.maintenance_date),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                serde_json::to_string_pretty(&licenses)?
+            },
+            _ => self.licenses.iter().map(|(key, _)| format!("{key}\n")).collect(),
+        };
+
+        fs::write(path, contents)
+    }
+
+    fn inspector_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add(
+            egui::TextEdit::singleline(&mut self.inspect_key)
+                .hint_text("Paste a key to inspect")
+                .font(egui::TextStyle::Monospace)
+                .desired_width(f32::INFINITY),
+        );
+        ui.add_space(4.0);
+
+        if self.inspect_key.trim().is_empty() {
+            return;
+        }
+
+        license_details_ui(ui, "inspector_grid", &self.inspect_key, None);
+    }
+}
+
+impl eframe::App for App {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.apply_style(ctx, frame);
+        self.poll_generation(ctx);
+        self.watch_clipboard(ctx);
+
+        self.toasts.show(ctx);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.tab, Tab::Generate, "Generate");
+                ui.selectable_value(&mut self.tab, Tab::Inspect, "Inspect");
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    ui.menu_button("Settings", |ui| self.settings_menu_ui(ui));
+                    ui.checkbox(&mut self.clipboard_watch, "Watch Clipboard")
+                        .on_hover_text("Validate keys as soon as they are copied anywhere");
+                });
+            });
+            ui.separator();
+
+            match self.tab {
+                Tab::Generate => self.generator_ui(ui),
+                Tab::Inspect => self.inspector_ui(ui),
+            }
+        });
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.settings());
+    }
+}
+
+/// Parses `key` and shows its fields in a grid, when `generated` is given the decoded fields are
+/// also compared against the license the key was generated from
+fn license_details_ui(ui: &mut egui::Ui, id: &str, key: &str, generated: Option<&License>) {
+    let license = match License::from_key(key) {
+        Ok(license) => license,
+        Err(e) => {
+            ui.colored_label(egui::Color32::RED, format!("Invalid key: {e}"));
+            return;
+        },
+    };
+
+    let today = Utc::today();
+    let expiry = match (license.expiry_date(), license.remaining_days(today)) {
+        (Some(expiry), Some(days)) => format!("{} ({days} days left)", format_date(expiry)),
+        _ => "Never".to_string(),
+    };
+    let maintenance = format!(
+        "{} ({} days left)",
+        format_date(license.maintenance_expiry_date()),
+        license.maintenance_remaining(today)
+    );
+
+    ui.label(license.summary());
+    egui::Grid::new(id).num_columns(2).striped(true).show(ui, |ui| {
+        ui.label("Edition");
+        ui.label(license.edition().to_string());
+        ui.end_row();
+
+        ui.label("Seats");
+        ui.label(license.seats().to_string());
+        ui.end_row();
+
+        ui.label("Purchase Date");
+        ui.label(format_date(license.purchase_date()));
+        ui.end_row();
+
+        ui.label("Expire Date");
+        ui.label(expiry);
+        ui.end_row();
+
+        ui.label("Maintenance Expire Date");
+        ui.label(maintenance);
+        ui.end_row();
+
+        ui.label("Valid");
+        match license.is_valid_key() {
+            true => ui.colored_label(egui::Color32::GREEN, "Yes"),
+            false => ui.colored_label(egui::Color32::RED, "No"),
+        };
+        ui.end_row();
+
+        if let Some(generated) = generated {
+            ui.label("Round Trip");
+            match generated.verify_key(key) {
+                Ok(()) => ui.colored_label(egui::Color32::GREEN, "Matches"),
+                Err(e) => ui.colored_label(egui::Color32::RED, e.to_string()),
+            };
+            ui.end_row();
+        }
+    });
+}
+
+/// Grayscale pixels of the QR code for `key`, with every module drawn as a `QR_SCALE` pixel square
+/// and the 4 module quiet zone scanners expect, and the side length in pixels
+fn qr_pixels(key: &str) -> Result<(usize, Vec<u8>), KeyError> {
+    let qr = KeyQr::new(key)?;
+    let modules = 0..qr.width();
+    let side = (qr.width() + 2 * QR_QUIET_ZONE) * QR_SCALE;
+
+    // ? INFO: coordinates in the quiet zone wrap around and end up outside of the modules
+    let pixels = (0..side * side).map(|idx| {
+        let x = (idx % side / QR_SCALE).wrapping_sub(QR_QUIET_ZONE);
+        let y = (idx / side / QR_SCALE).wrapping_sub(QR_QUIET_ZONE);
+
+        match modules.contains(&x) && modules.contains(&y) && qr.is_dark(x, y) {
+            true => 0,
+            false => u8::MAX,
+        }
+    });
+
+    Ok((side, pixels.collect()))
+}
+
+fn save_qr_png(key: &str, path: &Path) -> io::Result<()> {
+    let (side, pixels) =
+        qr_pixels(key).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    let mut encoder = png::Encoder::new(fs::File::create(path)?, side as u32, side as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&pixels)?;
+
+    Ok(())
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn share_mailto(key: &str, license: &License) -> String {
+    let expiry = match license.expiry_date() {
+        Some(expiry) => format_date(expiry),
+        None => "Never".to_string(),
+    };
+
+    let subject = format!("Your AIDA64 {} license key", license.edition());
+    let body = format!(
+        "License key: {key}\n\nEdition: {}\nSeats: {}\nPurchase date: {}\nExpires: {expiry}\nMaintenance until: {}\n",
+        license.edition(),
+        license.seats(),
+        format_date(license.purchase_date()),
+        format_date(license.maintenance_expiry_date()),
+    );
+
+    format!("mailto:?subject={}&body={}", percent_encode(&subject), percent_encode(&body))
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            },
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
 }
 
 fn main() {
     let options = eframe::NativeOptions {
         always_on_top: true,
         drag_and_drop_support: false,
-        resizable: false,
-        initial_window_size: Some(eframe::egui::Vec2::new(520.0, 300.0)),
+        initial_window_size: Some(eframe::egui::Vec2::new(620.0, 360.0)),
+        min_window_size: Some(eframe::egui::Vec2::new(2.0 * MIN_PANEL_WIDTH, 300.0)),
         ..Default::default()
     };
 
-    eframe::run_native("Key Generator", options, Box::new(|_cc| Box::<App>::default()));
+    eframe::run_native("Key Generator", options, Box::new(|cc| Box::new(App::new(cc))));
 }
@@ -0,0 +1,25 @@
+use aida64_keys_lib::{checksum, KeyEdition, License};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+fn bench_checksum(c: &mut Criterion) {
+    let keys = (0..1024)
+        .map(|_| License::new(KeyEdition::Extreme).generate().into_bytes())
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("checksum");
+    group.throughput(Throughput::Elements(keys.len() as u64));
+
+    group.bench_function("compute", |b| {
+        b.iter(|| {
+            keys.iter().map(|key| checksum::compute(black_box(&key[0..24])) as u32).sum::<u32>()
+        })
+    });
+    group.bench_function("verify", |b| {
+        b.iter(|| keys.iter().filter(|key| checksum::verify(black_box(key))).count())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_checksum);
+criterion_main!(benches);
@@ -0,0 +1,47 @@
+use aida64_keys_lib::{KeyEdition, License};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn generate(c: &mut Criterion) {
+    let license = License::new(KeyEdition::Extreme).with_seats(5);
+    let mut rng = StdRng::seed_from_u64(0xA1DA);
+
+    let mut group = c.benchmark_group("generate");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("generate_into", |b| {
+        let mut key = [0; 25];
+        b.iter(|| license.generate_into_with_rng(black_box(&mut key), &mut rng))
+    });
+    group.bench_function("generate_string", |b| {
+        b.iter(|| black_box(&license).generate_string_with_rng(true, &mut rng))
+    });
+
+    group.finish();
+}
+
+fn generate_bulk(c: &mut Criterion) {
+    let license = License::new(KeyEdition::Extreme).with_seats(5);
+    let mut rng = StdRng::seed_from_u64(0xA1DA);
+
+    let mut group = c.benchmark_group("generate_bulk");
+
+    for count in [100, 1000] {
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::new("generate_many", count), &count, |b, &count| {
+            b.iter(|| license.generate_many_with_rng(count, &mut rng))
+        });
+        #[cfg(feature = "parallel")]
+        group.bench_with_input(
+            BenchmarkId::new("par_generate_batch", count),
+            &count,
+            |b, &count| b.iter(|| license.par_generate_batch(count)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, generate, generate_bulk);
+criterion_main!(benches);
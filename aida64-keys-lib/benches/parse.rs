@@ -0,0 +1,45 @@
+use aida64_keys_lib::{codec, KeyEdition, License};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+/// Checked decoding with the linear alphabet scans used before the lookup table, as a baseline
+fn decode_linear(chars: &[u8]) -> Option<i32> {
+    if chars.iter().any(|b| !codec::ALPHABET.contains(b)) {
+        return None;
+    }
+
+    Some(chars.iter().fold(0i32, |result, c1| {
+        (result * codec::ALPHABET.len() as i32)
+            + codec::ALPHABET.iter().position(|c2| c2 == c1).unwrap_or(0) as i32
+    }))
+}
+
+/// The five dash separated groups of every key
+fn groups(keys: &[String]) -> impl Iterator<Item = &[u8]> {
+    keys.iter().flat_map(|key| key.as_bytes().split(|&b| b == b'-'))
+}
+
+fn parse(c: &mut Criterion) {
+    let keys = (0..1024)
+        .map(|_| License::new(KeyEdition::Extreme).generate_string(true))
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Elements(keys.len() as u64));
+
+    group.bench_function("from_key", |b| {
+        b.iter(|| keys.iter().filter(|key| License::from_key(black_box(key)).is_ok()).count())
+    });
+    group.bench_function("decode_base34", |b| {
+        b.iter(|| {
+            groups(&keys).map(|chars| codec::decode_base34(black_box(chars)).unwrap()).sum::<i32>()
+        })
+    });
+    group.bench_function("decode_linear", |b| {
+        b.iter(|| groups(&keys).map(|chars| decode_linear(black_box(chars)).unwrap()).sum::<i32>())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
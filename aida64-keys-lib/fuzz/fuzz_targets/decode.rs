@@ -0,0 +1,19 @@
+#![no_main]
+
+use aida64_keys_lib::{bulk, checksum, codec, ChecksumTrace, License};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(trace) = ChecksumTrace::new(data) {
+        if data.len() == 25 && data.iter().all(u8::is_ascii_alphanumeric) {
+            assert_eq!(trace.is_valid(), checksum::verify(data));
+        }
+    }
+
+    if let Ok(value) = codec::decode_base34(data) {
+        assert_eq!(codec::encode_base34(value, data.len()).as_bytes(), data);
+    }
+
+    let _ = License::decode_parts(data);
+    let _ = bulk::find_keys_in_text(&String::from_utf8_lossy(data));
+});
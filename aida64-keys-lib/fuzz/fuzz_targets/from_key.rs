@@ -0,0 +1,21 @@
+#![no_main]
+
+use aida64_keys_lib::License;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let license = match License::from_key(data) {
+        Ok(license) => license,
+        Err(_) => return,
+    };
+
+    license.is_valid_key();
+    license.expiry_date();
+    license.maintenance_expiry_date();
+
+    // ? INFO: the first round can normalize fields like out of range purchase years, after that
+    // generating and parsing has to be lossless
+    let regenerated = License::from_key(license.generate()).expect("regenerated key must parse");
+    let reparsed = License::from_key(regenerated.to_string()).expect("regenerated key must parse");
+    assert_eq!(reparsed, regenerated);
+});
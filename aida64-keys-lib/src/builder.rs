@@ -0,0 +1,164 @@
+//! Date based license builder that validates every field at once
+
+use chrono::NaiveDate;
+
+use crate::compat::IntoNaiveDate;
+use crate::{KeyEdition, KeyError, License};
+
+/// Type state of a [`LicenseBuilder`] without an edition, which can't be built yet
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoEdition;
+
+/// Type state of a [`LicenseBuilder`] with an edition
+#[derive(Debug, Clone, Copy)]
+pub struct WithEdition(KeyEdition);
+
+/// Collects license fields as absolute dates and only validates them in [`LicenseBuilder::build`],
+/// so unlike the `with_*` methods on [`License`] the order of the calls doesn't matter. Fields
+/// that are never set keep the defaults of [`License::new`]
+#[derive(Debug, Clone, Default)]
+pub struct LicenseBuilder<E = NoEdition> {
+    edition: E,
+    seats: Option<i32>,
+    purchase_date: Option<NaiveDate>,
+    expiry_date: Option<NaiveDate>,
+    maintenance_date: Option<NaiveDate>,
+    components: Option<[i32; 3]>,
+    base_pair: Option<[u8; 2]>,
+}
+
+impl LicenseBuilder<NoEdition> {
+    pub fn new() -> LicenseBuilder<NoEdition> {
+        LicenseBuilder::default()
+    }
+}
+
+impl<E> LicenseBuilder<E> {
+    pub fn edition(self, edition: KeyEdition) -> LicenseBuilder<WithEdition> {
+        LicenseBuilder {
+            edition: WithEdition(edition),
+            seats: self.seats,
+            purchase_date: self.purchase_date,
+            expiry_date: self.expiry_date,
+            maintenance_date: self.maintenance_date,
+            components: self.components,
+            base_pair: self.base_pair,
+        }
+    }
+
+    pub fn seats(mut self, seats: i32) -> Self {
+        self.seats = Some(seats);
+        self
+    }
+
+    pub fn purchase_date<D: IntoNaiveDate>(mut self, date: D) -> Self {
+        self.purchase_date = Some(date.into_naive_date());
+        self
+    }
+
+    /// Day the license expires, never if not set
+    pub fn expiry_date<D: IntoNaiveDate>(mut self, date: D) -> Self {
+        self.expiry_date = Some(date.into_naive_date());
+        self
+    }
+
+    /// Day the maintenance expires, the longest possible period if not set
+    pub fn maintenance_date<D: IntoNaiveDate>(mut self, date: D) -> Self {
+        self.maintenance_date = Some(date.into_naive_date());
+        self
+    }
+
+    pub fn components(mut self, components: [i32; 3]) -> Self {
+        self.components = Some(components);
+        self
+    }
+
+    pub fn base_pair(mut self, pair: [u8; 2]) -> Self {
+        self.base_pair = Some(pair);
+        self
+    }
+}
+
+impl LicenseBuilder<WithEdition> {
+    /// Validates every field that was set, errors instead of clamping out of range values and
+    /// when an expiry isn't after the purchase date
+    pub fn build(self) -> Result<License, KeyError> {
+        let mut license = License::new(self.edition.0);
+
+        // ? INFO: the purchase date goes first, both expiries are stored relative to it
+        if let Some(date) = self.purchase_date {
+            license = license.try_with_purchase_date(date)?;
+        }
+        if let Some(seats) = self.seats {
+            license = license.try_with_seats(seats)?;
+        }
+        if let Some(date) = self.expiry_date {
+            license = license.with_expiry_date(date)?;
+        }
+        if let Some(date) = self.maintenance_date {
+            license = license.with_maintenance_expiry_date(date)?;
+        }
+        if let Some(components) = self.components {
+            license = license.with_components(components)?;
+        }
+        if let Some(pair) = self.base_pair {
+            license = license.with_base_pair(Some(pair))?;
+        }
+
+        Ok(license)
+    }
+}
+
+impl License {
+    /// Shorthand for [`LicenseBuilder::new`]
+    pub fn builder() -> LicenseBuilder<NoEdition> {
+        LicenseBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn build() {
+        let purchase = NaiveDate::from_ymd(2024, 1, 1);
+
+        // ? INFO: expiries set before the purchase date still resolve against it
+        let license = License::builder()
+            .expiry_date(NaiveDate::from_ymd(2024, 12, 31))
+            .maintenance_date(NaiveDate::from_ymd(2026, 1, 1))
+            .seats(10)
+            .purchase_date(purchase)
+            .edition(KeyEdition::Engineer)
+            .build()
+            .unwrap();
+
+        assert_eq!(license.edition(), KeyEdition::Engineer);
+        assert_eq!(license.seats(), 10);
+        assert_eq!(license.purchase_date(), purchase);
+        assert_eq!(license.expiry(), Some(Duration::days(365)));
+        assert_eq!(license.maintenance_expiry(), Duration::days(731));
+    }
+
+    #[test]
+    fn build_errors() {
+        let builder = License::builder().edition(KeyEdition::Extreme);
+
+        assert!(matches!(
+            builder
+                .clone()
+                .purchase_date(NaiveDate::from_ymd(2024, 1, 1))
+                .expiry_date(NaiveDate::from_ymd(2023, 1, 1))
+                .build(),
+            Err(KeyError::DateOutOfRange { .. })
+        ));
+        assert!(matches!(
+            builder.clone().seats(0).build(),
+            Err(KeyError::ValueOutOfRange { min: 1, max: 797, found: 0 })
+        ));
+        assert!(builder.build().unwrap().is_valid_key());
+    }
+}
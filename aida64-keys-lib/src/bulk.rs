@@ -0,0 +1,159 @@
+//! Validating whole key lists and finding keys in free text
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::{codec, KeyError, License};
+
+#[derive(Debug)]
+pub struct InvalidEntry {
+    /// Byte offset of the start of the entry in the scanned data
+    pub offset: usize,
+    /// 1-based line number of the entry
+    pub line: usize,
+    pub error: KeyError,
+}
+
+/// A key shaped run of text, either 25 key characters or five dash separated groups of five
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyCandidate<'a> {
+    /// Byte offset of the candidate in the scanned text
+    pub offset: usize,
+    pub text: &'a str,
+}
+
+#[derive(Debug, Default)]
+pub struct BulkReport {
+    pub total: usize,
+    pub valid: usize,
+    pub invalid: Vec<InvalidEntry>,
+}
+
+/// Memory-maps the file at `path` and validates every non-empty line as a key
+pub fn validate_file<P: AsRef<Path>>(path: P) -> io::Result<BulkReport> {
+    let file = File::open(path)?;
+
+    // ? INFO: mapping an empty file fails on some platforms
+    if file.metadata()?.len() == 0 {
+        return Ok(BulkReport::default());
+    }
+
+    // SAFETY: the map is read-only and only lives for the duration of the scan, a concurrent
+    // writer truncating the file is outside of what this can guard against
+    let map = unsafe { Mmap::map(&file)? };
+    Ok(validate_bytes(&map))
+}
+
+/// Validates every non-empty line of `data` as a key
+pub fn validate_bytes(data: &[u8]) -> BulkReport {
+    let mut report = BulkReport::default();
+    let mut offset = 0;
+
+    for (idx, line) in data.split(|b| *b == b'\n').enumerate() {
+        let start = offset;
+        offset += line.len() + 1;
+
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+
+        report.total += 1;
+
+        match validate_key(line) {
+            Ok(_) => report.valid += 1,
+            Err(error) => report.invalid.push(InvalidEntry { offset: start, line: idx + 1, error }),
+        }
+    }
+
+    report
+}
+
+/// Parses a single entry, treating keys that decode but fail [`License::is_valid_key`] as
+/// [`KeyError::InvalidLicense`]
+pub fn validate_key<T: AsRef<[u8]>>(key: T) -> Result<License, KeyError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("validate_key").entered();
+
+    License::from_key(key).and_then(|license| match license.is_valid_key() {
+        true => Ok(license),
+        false => Err(KeyError::InvalidLicense),
+    })
+}
+
+/// Finds every key shaped candidate in free text such as logs or mail dumps, candidates still
+/// have to be parsed to know whether they are actual keys
+pub fn find_keys_in_text(text: &str) -> Vec<KeyCandidate<'_>> {
+    let bytes = text.as_bytes();
+    let mut candidates = Vec::new();
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        let at_boundary = idx == 0 || !is_key_byte(bytes[idx - 1]);
+        let len = [29, 25].into_iter().find(|&len| at_boundary && matches_key_at(bytes, idx, len));
+
+        match len {
+            Some(len) => {
+                candidates.push(KeyCandidate { offset: idx, text: &text[idx..idx + len] });
+                idx += len;
+            },
+            None => idx += 1,
+        }
+    }
+
+    candidates
+}
+
+fn is_key_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'-'
+}
+
+fn matches_key_at(bytes: &[u8], start: usize, len: usize) -> bool {
+    let candidate = match bytes.get(start..start + len) {
+        Some(candidate) => candidate,
+        None => return false,
+    };
+
+    // ? INFO: a key directly followed by more key-ish characters is part of something longer
+    if bytes.get(start + len).is_some_and(|&byte| is_key_byte(byte)) {
+        return false;
+    }
+
+    candidate.iter().enumerate().all(|(idx, byte)| match len == 29 && idx % 6 == 5 {
+        true => *byte == b'-',
+        false => codec::digit(*byte).is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let data = b"3BH41-94ZD6-4KDT5-JDPUY-TBSN9\r\n\n  \n3BH41-94ZD6-4KDT5-JDPUY-TBSN2\nshort\n";
+        let report = validate_bytes(data);
+
+        assert_eq!(report.total, 3);
+        assert_eq!(report.valid, 1);
+        assert_eq!(report.invalid.len(), 2);
+        assert_eq!((report.invalid[0].offset, report.invalid[0].line), (35, 4));
+        assert!(matches!(report.invalid[1].error, KeyError::InvalidLength { .. }));
+    }
+
+    #[test]
+    fn find_keys() {
+        let text = "key: 3BH41-94ZD6-4KDT5-JDPUY-TBSN9, again 3BH4194ZD64KDT5JDPUYTBSN9.\n\
+                    not keys: 3BH41-94ZD6-4KDT5-JDPUY-TBSN9X 3bH41-94ZD6-4KDT5-JDPUY-TBSN9";
+        let candidates = find_keys_in_text(text);
+
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(
+            candidates[0],
+            KeyCandidate { offset: 5, text: "3BH41-94ZD6-4KDT5-JDPUY-TBSN9" }
+        );
+        assert_eq!(candidates[1].text, "3BH4194ZD64KDT5JDPUYTBSN9");
+    }
+}
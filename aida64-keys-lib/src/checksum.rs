@@ -0,0 +1,181 @@
+//! The CRC-style checksum guarding the 25th key character
+
+use alloc::vec::Vec;
+use core::num::NonZeroU16;
+
+use crate::codec::enc_part;
+use crate::KeyError;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ChecksumStep {
+    pub byte: u8,
+    pub register: u32,
+}
+
+/// Step by step record of the checksum computation over the first 24 key characters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumTrace {
+    pub steps: Vec<ChecksumStep>,
+    pub register: u16,
+    pub checksum: u16,
+    pub encoded: [u8; 3],
+    pub expected: u8,
+    pub found: Option<u8>,
+}
+
+impl ChecksumTrace {
+    /// Traces the checksum of a key with or without its trailing check character
+    pub fn new<T: AsRef<[u8]>>(key: T) -> Result<ChecksumTrace, KeyError> {
+        Checksum::AIDA64.trace(key)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.found == Some(self.expected)
+    }
+}
+
+/// Parameters of the checksum, [`Checksum::AIDA64`] is the one keys use and the one the free
+/// functions of this module compute
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Checksum {
+    /// XORed into the register whenever its bit 15 is shifted out
+    pub polynomial: u16,
+    /// The low 16 bits of the register are reduced modulo this
+    pub modulus: NonZeroU16,
+}
+
+impl Checksum {
+    pub const AIDA64: Checksum =
+        Checksum { polynomial: 0x8201, modulus: NonZeroU16::new(0x9987).unwrap() };
+
+    /// Feeds one byte into the checksum register
+    pub fn step(&self, register: u32, b: u8) -> u32 {
+        (0..8).fold(register ^ (b as u32) << 8, |result, _| {
+            if result & 0x8000 == 0 {
+                result << 1
+            } else {
+                result << 1 ^ self.polynomial as u32
+            }
+        })
+    }
+
+    /// Checksum over the first 24 key characters
+    pub fn compute<T: AsRef<[u8]>>(&self, key_part: T) -> u16 {
+        let register = (key_part.as_ref().iter().fold(0u32, |result, b| self.step(result, *b))
+            & 0xFFFF) as u16;
+
+        register % self.modulus
+    }
+
+    /// The 25th key character for the first 24 key characters
+    pub fn check_char<T: AsRef<[u8]>>(&self, key_part: T) -> u8 {
+        let mut enc_checksum: [u8; 3] = [0; 3];
+        enc_part(self.compute(key_part) as i32, &mut enc_checksum);
+
+        enc_checksum[1]
+    }
+
+    /// Whether `key` is 25 characters long and ends with the right check character, `key` must
+    /// not contain separators
+    pub fn verify<T: AsRef<[u8]>>(&self, key: T) -> bool {
+        let key = key.as_ref();
+        key.len() == 25 && self.check_char(&key[0..24]) == key[24]
+    }
+
+    /// Traces the checksum of a key with or without its trailing check character
+    pub fn trace<T: AsRef<[u8]>>(&self, key: T) -> Result<ChecksumTrace, KeyError> {
+        let key =
+            key.as_ref().iter().filter(|b| b.is_ascii_alphanumeric()).copied().collect::<Vec<u8>>();
+
+        if key.len() != 24 && key.len() != 25 {
+            return Err(KeyError::InvalidLength { expected: 25, found: key.len() });
+        }
+
+        let steps = key[0..24]
+            .iter()
+            .scan(0u32, |register, &byte| {
+                *register = self.step(*register, byte);
+                Some(ChecksumStep { byte, register: *register })
+            })
+            .collect::<Vec<_>>();
+
+        let register = (steps.last().map(|step| step.register).unwrap_or(0) & 0xFFFF) as u16;
+        let checksum = register % self.modulus;
+
+        let mut encoded: [u8; 3] = [0; 3];
+        enc_part(checksum as i32, &mut encoded);
+
+        Ok(ChecksumTrace {
+            steps,
+            register,
+            checksum,
+            encoded,
+            expected: encoded[1],
+            found: key.get(24).copied(),
+        })
+    }
+}
+
+impl Default for Checksum {
+    fn default() -> Checksum {
+        Checksum::AIDA64
+    }
+}
+
+/// Feeds one byte into the checksum register
+pub fn step(register: u32, b: u8) -> u32 {
+    Checksum::AIDA64.step(register, b)
+}
+
+/// Checksum over the first 24 key characters
+pub fn compute<T: AsRef<[u8]>>(key_part: T) -> u16 {
+    Checksum::AIDA64.compute(key_part)
+}
+
+/// The 25th key character for the first 24 key characters
+pub fn check_char<T: AsRef<[u8]>>(key_part: T) -> u8 {
+    Checksum::AIDA64.check_char(key_part)
+}
+
+/// Whether `key` is 25 characters long and ends with the right check character, `key` must
+/// not contain separators
+pub fn verify<T: AsRef<[u8]>>(key: T) -> bool {
+    Checksum::AIDA64.verify(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum() {
+        assert!(verify(b"3BH4194ZD64KDT5JDPUYTBSN9"));
+        assert!(!verify(b"3BH4194ZD64KDT5JDPUYTBSN2"));
+        assert!(!verify(b"3BH41-94ZD6-4KDT5-JDPUY-TBSN9"));
+        assert_eq!(check_char(b"3BH4194ZD64KDT5JDPUYTBSN"), b'9');
+    }
+
+    #[test]
+    fn trace() {
+        let trace = ChecksumTrace::new("3BH41-94ZD6-4KDT5-JDPUY-TBSN9").unwrap();
+        assert!(trace.is_valid(), "traced checksum does not match a valid key!");
+        assert_eq!(trace.checksum, compute(b"3BH4194ZD64KDT5JDPUYTBSN"));
+
+        let trace = ChecksumTrace::new("3BH41-94ZD6-4KDT5-JDPUY-TBSN").unwrap();
+        assert_eq!(trace.found, None);
+        assert_eq!(trace.expected, b'9');
+    }
+
+    #[test]
+    fn parameters() {
+        let key = b"3BH4194ZD64KDT5JDPUYTBSN";
+        assert_eq!(Checksum::default().compute(key), compute(key));
+
+        let variant = Checksum { polynomial: 0x1021, ..Checksum::AIDA64 };
+        assert_ne!(variant.compute(key), compute(key));
+        assert_eq!(variant.trace(key).unwrap().checksum, variant.compute(key));
+
+        let variant = Checksum { modulus: NonZeroU16::new(34).unwrap(), ..Checksum::AIDA64 };
+        assert!(variant.compute(key) < 34);
+    }
+}
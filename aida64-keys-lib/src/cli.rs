@@ -0,0 +1,176 @@
+//! Value parsers for clap based tools, [`KeyEdition`](crate::KeyEdition) also implements
+//! `clap::ValueEnum` with this feature
+
+use chrono::{Duration, NaiveDate};
+
+use crate::{DateStep, MaintenancePeriod};
+
+/// A license or maintenance length as given on the command line, see [`parse_length`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Length {
+    /// Counted from the purchase date
+    Duration(Duration),
+    /// Calendar years after the purchase date, like [`MaintenancePeriod::years`]
+    Years(u32),
+    /// Ends on this date
+    Until(NaiveDate),
+    /// The longest period a key can encode, [`MaintenancePeriod::max`]
+    Max,
+}
+
+impl Length {
+    /// The length counted from `purchase`, an error when it's shorter than a day or longer than a
+    /// key can encode instead of clamping it like the license builders do
+    pub fn resolve(self, purchase: NaiveDate) -> Result<Duration, String> {
+        let max = MaintenancePeriod::max();
+        let duration = match self {
+            Length::Duration(duration) => duration,
+            // ? INFO: a year from February 29th ends on February 28th, a date past what chrono can
+            // represent is too long either way
+            Length::Years(years) => DateStep::Month
+                .nth(purchase, years.saturating_mul(12))
+                .map_or_else(Duration::max_value, |end| end - purchase),
+            Length::Until(date) => date - purchase,
+            Length::Max => return Ok(max),
+        };
+
+        match self {
+            _ if (Duration::days(1)..=max).contains(&duration) => Ok(duration),
+            Length::Until(date) if duration < Duration::days(1) => {
+                Err(format!("{date} is not after the purchase date {purchase}"))
+            },
+            Length::Until(date) => Err(format!(
+                "{date} is more than {} days after the purchase date {purchase}",
+                max.num_days()
+            )),
+            _ if duration < Duration::days(1) => Err("a length has to be at least 1 day".into()),
+            Length::Years(years) => Err(format!(
+                "{years} years is longer than the {} days a key can encode, use max for the longest",
+                max.num_days()
+            )),
+            _ => Err(format!(
+                "{} days is longer than the {} days a key can encode, use max for the longest",
+                duration.num_days(),
+                max.num_days()
+            )),
+        }
+    }
+}
+
+/// Parses a [`parse_duration`] length, a `YYYY-MM-DD` end date or `max`, years are calendar
+/// years counted from the purchase date instead of 365 days
+pub fn parse_length(value: &str) -> Result<Length, String> {
+    let value = value.trim();
+    let error =
+        || format!("expected days, weeks (w), years (y), a YYYY-MM-DD date or max, not '{value}'");
+
+    if value.eq_ignore_ascii_case("max") {
+        return Ok(Length::Max);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(Length::Until(date));
+    }
+    if let Some(years) = value.strip_suffix(['y', 'Y']) {
+        return years.parse().map(Length::Years).map_err(|_| error());
+    }
+
+    parse_duration(value).map(Length::Duration).map_err(|_| error())
+}
+
+/// Parses a length like `30`, `30d`, `6w` or `2y` into a [`Duration`], bare numbers are days and
+/// years count as 365 days, see [`parse_length`] for calendar years
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim().to_ascii_lowercase();
+    let (number, days_per_unit) = match value.char_indices().last() {
+        Some((idx, 'd')) => (&value[..idx], 1),
+        Some((idx, 'w')) => (&value[..idx], 7),
+        Some((idx, 'y')) => (&value[..idx], 365),
+        _ => (value.as_str(), 1),
+    };
+
+    number
+        .parse::<i64>()
+        .ok()
+        .and_then(|n| n.checked_mul(days_per_unit))
+        .filter(|days| (0..=i32::MAX as i64).contains(days))
+        .map(Duration::days)
+        .ok_or_else(|| format!("expected a number of days, weeks (w) or years (y), not '{value}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::ValueEnum;
+
+    use super::*;
+    use crate::KeyEdition;
+
+    #[test]
+    fn editions() {
+        let parse = <KeyEdition as ValueEnum>::from_str;
+
+        for edition in KeyEdition::all() {
+            assert_eq!(parse(edition.slug(), false), Ok(edition));
+        }
+        assert_eq!(parse("NA", true), Ok(KeyEdition::NetworkAudit));
+        assert!(parse("network audit", true).is_err());
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("30"), Ok(Duration::days(30)));
+        assert_eq!(parse_duration("30D"), Ok(Duration::days(30)));
+        assert_eq!(parse_duration("6w"), Ok(Duration::days(42)));
+        assert_eq!(parse_duration(" 2y "), Ok(Duration::days(730)));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("-1").is_err());
+        assert!(parse_duration("3m").is_err());
+        assert!(parse_duration("99999999999y").is_err());
+    }
+
+    #[test]
+    fn lengths() {
+        let purchase = NaiveDate::from_ymd(2024, 1, 1);
+        let resolve = |value| parse_length(value).and_then(|length| length.resolve(purchase));
+
+        assert_eq!(resolve("90d"), Ok(Duration::days(90)));
+        assert_eq!(resolve("1y"), Ok(Duration::days(366)));
+        assert_eq!(parse_length(" 2Y "), Ok(Length::Years(2)));
+        assert_eq!(resolve("2024-12-31"), Ok(Duration::days(365)));
+        assert_eq!(resolve(" MAX "), Ok(MaintenancePeriod::max()));
+        assert_eq!(
+            parse_length("2025-12-31"),
+            Ok(Length::Until(NaiveDate::from_ymd(2025, 12, 31)))
+        );
+
+        assert!(parse_length("2025-13-01").is_err());
+        assert!(parse_length("forever").is_err());
+        assert!(resolve("0").is_err());
+        assert!(resolve("11y").is_err());
+        assert!(resolve("0y").is_err());
+        assert!(resolve("4294967295y").is_err());
+        assert!(parse_length("-1y").is_err());
+        assert!(parse_length("y").is_err());
+        assert!(resolve("2024-01-01").is_err());
+        assert!(resolve("2040-01-01").is_err());
+    }
+
+    #[test]
+    fn leap_years() {
+        let purchase = NaiveDate::from_ymd(2024, 2, 29);
+        let resolve = |value| parse_length(value).and_then(|length| length.resolve(purchase));
+
+        assert_eq!(resolve("1y"), Ok(MaintenancePeriod::years(purchase, 1)));
+        assert_eq!(
+            resolve("1y").map(|length| purchase + length),
+            Ok(NaiveDate::from_ymd(2025, 2, 28))
+        );
+        assert_eq!(
+            resolve("4y").map(|length| purchase + length),
+            Ok(NaiveDate::from_ymd(2028, 2, 29))
+        );
+        assert_eq!(resolve("10y"), Ok(MaintenancePeriod::years(purchase, 10)));
+
+        let purchase = NaiveDate::from_ymd(2023, 3, 1);
+        assert_eq!(parse_length("1y").unwrap().resolve(purchase), Ok(Duration::days(366)));
+    }
+}
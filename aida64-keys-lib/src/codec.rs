@@ -0,0 +1,278 @@
+//! The base-34 codec every key segment is written in
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{KeyError, KEYS_SIZE, KEY_CHARS};
+
+/// The 34 characters keys are made of, in digit order
+pub const ALPHABET: [u8; 34] = KEY_CHARS;
+
+/// Digit value of every byte, [`INVALID_DIGIT`] for bytes outside the alphabet
+const DIGITS: [u8; 256] = {
+    let mut digits = [INVALID_DIGIT; 256];
+    let mut idx = 0;
+    while idx < KEY_CHARS.len() {
+        digits[KEY_CHARS[idx] as usize] = idx as u8;
+        idx += 1;
+    }
+    digits
+};
+const INVALID_DIGIT: u8 = u8::MAX;
+
+/// Longest run of characters [`decode_base34`] accepts without overflowing an `i32`
+pub const MAX_BASE34_WIDTH: usize = 6;
+
+/// Writes `value` as exactly `width` key characters, most significant first, wrapping values
+/// that don't fit
+pub fn encode_base34(value: i32, width: usize) -> String {
+    let mut encoded = alloc::vec![0; width];
+    enc_part(value, &mut encoded);
+    encoded.into_iter().map(char::from).collect()
+}
+
+/// Reads a run of key characters back into the value [`encode_base34`] wrote
+pub fn decode_base34<T: AsRef<[u8]>>(chars: T) -> Result<i32, KeyError> {
+    let chars = chars.as_ref();
+
+    if chars.len() > MAX_BASE34_WIDTH {
+        return Err(KeyError::InvalidLength { expected: MAX_BASE34_WIDTH, found: chars.len() });
+    }
+
+    chars.iter().enumerate().try_fold(0i32, |result, (index, &b)| match digit(b) {
+        Some(digit) => Ok(result * KEYS_SIZE + digit as i32),
+        None => Err(KeyError::InvalidCharacter { index, character: b as char }),
+    })
+}
+
+/// Length of a key with dashes between its five groups
+pub const DASHED_KEY_LEN: usize = 29;
+
+/// Writes `key` with dashes between its five groups into `out` without allocating
+pub fn format_into<'a>(
+    key: &[u8; 25],
+    out: &'a mut [u8; DASHED_KEY_LEN],
+) -> Result<&'a str, KeyError> {
+    check_alphabet(key)?;
+
+    for (group, chunk) in key.chunks(5).enumerate() {
+        out[group * 6..group * 6 + 5].copy_from_slice(chunk);
+        if group < 4 {
+            out[group * 6 + 5] = b'-';
+        }
+    }
+
+    // ? INFO: the alphabet and dashes are ASCII
+    Ok(core::str::from_utf8(out).unwrap())
+}
+
+/// How [`format_key`] writes a key, [`KeyStyle::DASHED`] by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyStyle {
+    /// Written between the five groups, `None` writes the 25 characters in one run
+    pub separator: Option<char>,
+    pub lowercase: bool,
+}
+
+impl KeyStyle {
+    /// Uppercase groups of five separated by dashes, the form keys are displayed in
+    pub const DASHED: KeyStyle = KeyStyle { separator: Some('-'), lowercase: false };
+    /// All 25 characters in one uppercase run
+    pub const CONTINUOUS: KeyStyle = KeyStyle { separator: None, lowercase: false };
+
+    pub fn with_separator(mut self, separator: Option<char>) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn with_lowercase(mut self, lowercase: bool) -> Self {
+        self.lowercase = lowercase;
+        self
+    }
+}
+
+impl Default for KeyStyle {
+    fn default() -> KeyStyle {
+        KeyStyle::DASHED
+    }
+}
+
+/// Writes `key` in the given style
+pub fn format_key(key: &[u8; 25], style: KeyStyle) -> Result<String, KeyError> {
+    check_alphabet(key)?;
+
+    let mut out = String::with_capacity(DASHED_KEY_LEN);
+    for (group, chunk) in key.chunks(5).enumerate() {
+        if let Some(separator) = style.separator.filter(|_| group > 0) {
+            out.push(separator);
+        }
+
+        out.extend(chunk.iter().map(|&b| match style.lowercase {
+            true => b.to_ascii_lowercase() as char,
+            false => b as char,
+        }));
+    }
+
+    Ok(out)
+}
+
+/// Normalizes any separators, whitespace and lowercase in `key` away and writes it in the dashed
+/// uppercase form, only the length and alphabet are checked, not the checksum
+pub fn canonicalize(key: &str) -> Result<String, KeyError> {
+    let mut key = collect_key(key)?;
+    fold_key(&mut key, false);
+
+    format_key(&key, KeyStyle::DASHED)
+}
+
+/// A character lenient parsing replaced, see [`fold_key`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Substitution {
+    /// Position in the key with separators removed
+    pub index: usize,
+    pub from: char,
+    pub to: char,
+}
+
+/// Characters outside the alphabet that are usually a misread alphabet character
+const CONFUSABLES: [(u8, u8); 2] = [(b'0', b'D'), (b'O', b'D')];
+
+/// Uppercases `key` in place and, when `lenient` is set, replaces characters that aren't in the
+/// alphabet but look like one that is (`0` and `O` become `D`), returning those replacements
+pub fn fold_key(key: &mut [u8; 25], lenient: bool) -> Vec<Substitution> {
+    let mut substitutions = Vec::new();
+
+    for (index, b) in key.iter_mut().enumerate() {
+        b.make_ascii_uppercase();
+
+        let replacement = CONFUSABLES.iter().find(|(from, _)| lenient && from == b);
+        if let Some(&(from, to)) = replacement {
+            *b = to;
+            substitutions.push(Substitution { index, from: from as char, to: to as char });
+        }
+    }
+
+    substitutions
+}
+
+/// Strips everything but ASCII letters and digits from `key` without checking the alphabet
+pub(crate) fn collect_key<T: AsRef<[u8]>>(key: T) -> Result<[u8; 25], KeyError> {
+    let mut normalized = [0; 25];
+    let mut len = 0;
+
+    for &b in key.as_ref().iter().filter(|b| b.is_ascii_alphanumeric()) {
+        if let Some(slot) = normalized.get_mut(len) {
+            *slot = b;
+        }
+        len += 1;
+    }
+
+    if len != 25 {
+        return Err(KeyError::InvalidLength { expected: 25, found: len });
+    }
+
+    Ok(normalized)
+}
+
+/// Fails on the first byte that isn't part of the key alphabet
+pub(crate) fn check_alphabet(chars: &[u8]) -> Result<(), KeyError> {
+    match chars.iter().position(|&b| digit(b).is_none()) {
+        Some(index) => Err(KeyError::InvalidCharacter { index, character: chars[index] as char }),
+        None => Ok(()),
+    }
+}
+
+/// Position of `b` in the key alphabet
+pub(crate) fn digit(b: u8) -> Option<u8> {
+    match DIGITS[b as usize] {
+        INVALID_DIGIT => None,
+        digit => Some(digit),
+    }
+}
+
+pub(crate) fn enc_part(mut val: i32, slice: &mut [u8]) {
+    // ? INFO: euclidean so out of range values decoded from crafted keys can't index out of bounds
+    slice.iter_mut().rev().for_each(|x| {
+        *x = KEY_CHARS[val.rem_euclid(KEYS_SIZE) as usize];
+        val = val.div_euclid(KEYS_SIZE);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base34() {
+        assert_eq!(encode_base34(0, 2), "DD");
+        assert_eq!(encode_base34(35, 2), "YY");
+        assert_eq!(decode_base34("YY").unwrap(), 35);
+
+        for value in [0, 1, 33, 34, 1155, 39303, 1_544_804_415] {
+            let encoded = encode_base34(value, MAX_BASE34_WIDTH);
+            assert_eq!(decode_base34(&encoded).unwrap(), value);
+        }
+
+        assert!(matches!(decode_base34("D0"), Err(KeyError::InvalidCharacter { index: 1, .. })));
+        assert!(decode_base34("DDDDDDD").is_err());
+    }
+
+    #[test]
+    fn digits() {
+        for (idx, &c) in KEY_CHARS.iter().enumerate() {
+            assert_eq!(digit(c), Some(idx as u8));
+        }
+
+        let invalid = (0..=u8::MAX).filter(|b| digit(*b).is_none()).count();
+        assert_eq!(invalid, 256 - KEY_CHARS.len());
+    }
+
+    #[test]
+    fn format() {
+        let mut out = [0; DASHED_KEY_LEN];
+        assert_eq!(
+            format_into(b"DY14UF3RHWCXLQB6IKJT9N5AG", &mut out).unwrap(),
+            "DY14U-F3RHW-CXLQB-6IKJT-9N5AG"
+        );
+        assert!(format_into(b"DY14UF3RHWCXLQB6IKJT9N5A0", &mut out).is_err());
+
+        let key = b"DY14UF3RHWCXLQB6IKJT9N5AG";
+        assert_eq!(format_key(key, KeyStyle::default()).unwrap(), "DY14U-F3RHW-CXLQB-6IKJT-9N5AG");
+        assert_eq!(format_key(key, KeyStyle::CONTINUOUS).unwrap(), "DY14UF3RHWCXLQB6IKJT9N5AG");
+        assert_eq!(
+            format_key(key, KeyStyle::DASHED.with_separator(Some(' ')).with_lowercase(true))
+                .unwrap(),
+            "dy14u f3rhw cxlqb 6ikjt 9n5ag"
+        );
+    }
+
+    #[test]
+    fn fold() {
+        let mut key = *b"dy14uf3rhwcxlqb6ikjt9n5ao";
+        assert!(fold_key(&mut key, false).is_empty());
+        assert_eq!(&key, b"DY14UF3RHWCXLQB6IKJT9N5AO");
+
+        let mut key = *b"0y14uf3rhwcxlqb6ikjt9n5ao";
+        assert_eq!(
+            fold_key(&mut key, true),
+            [
+                Substitution { index: 0, from: '0', to: 'D' },
+                Substitution { index: 24, from: 'O', to: 'D' }
+            ]
+        );
+        assert_eq!(&key, b"DY14UF3RHWCXLQB6IKJT9N5AD");
+    }
+
+    #[test]
+    fn canonical() {
+        assert_eq!(
+            canonicalize(" dy14U F3RHW_CXLQB6IKJT-9N5AG\n").unwrap(),
+            "DY14U-F3RHW-CXLQB-6IKJT-9N5AG"
+        );
+        assert!(matches!(canonicalize("DY14U"), Err(KeyError::InvalidLength { found: 5, .. })));
+        assert!(matches!(
+            canonicalize("DY14U-F3RHW-CXLQB-6IKJT-9N5A0"),
+            Err(KeyError::InvalidCharacter { index: 24, .. })
+        ));
+    }
+}
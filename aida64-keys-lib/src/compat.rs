@@ -0,0 +1,29 @@
+//! Helpers for migrating from the deprecated `chrono::Date` to `NaiveDate`, date parameters
+//! accept both so existing callers keep compiling
+
+use chrono::{Date, NaiveDate, TimeZone, Utc};
+
+use crate::License;
+
+pub trait IntoNaiveDate {
+    fn into_naive_date(self) -> NaiveDate;
+}
+
+impl IntoNaiveDate for NaiveDate {
+    fn into_naive_date(self) -> NaiveDate {
+        self
+    }
+}
+
+impl<Tz: TimeZone> IntoNaiveDate for Date<Tz> {
+    fn into_naive_date(self) -> NaiveDate {
+        self.naive_local()
+    }
+}
+
+impl License {
+    #[deprecated(note = "`purchase_date` is a `NaiveDate` now")]
+    pub fn purchase_date_utc(&self) -> Date<Utc> {
+        Date::from_utc(self.purchase_date(), Utc)
+    }
+}
@@ -0,0 +1,151 @@
+//! [`LicenseKey`], a key that is known to be well formed
+
+use core::fmt;
+use std::str::FromStr;
+
+use crate::codec::{self, collect_key, KeyStyle};
+use crate::scheme::{self, Aida64};
+use crate::KeyError;
+
+/// 25 key characters with a valid alphabet and check character, whether they decode to a valid
+/// license is up to [`License::from_key`](crate::License::from_key)
+///
+/// Displays with dashes, or without them with the alternate flag (`{:#}`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LicenseKey([u8; 25]);
+
+impl LicenseKey {
+    /// Validates `key`, separators are ignored
+    pub fn new<T: AsRef<[u8]>>(key: T) -> Result<LicenseKey, KeyError> {
+        LicenseKey::from_bytes(collect_key(key)?)
+    }
+
+    /// Validates a key without separators
+    pub fn from_bytes(key: [u8; 25]) -> Result<LicenseKey, KeyError> {
+        scheme::check_key::<Aida64>(&key)?;
+        Ok(LicenseKey(key))
+    }
+
+    /// Wraps a key that was just encoded, so is valid by construction
+    pub(crate) fn new_unchecked(key: [u8; 25]) -> LicenseKey {
+        debug_assert!(scheme::check_key::<Aida64>(&key).is_ok());
+        LicenseKey(key)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 25] {
+        &self.0
+    }
+
+    /// Same as [`LicenseKey::as_bytes`]
+    pub fn as_array(&self) -> &[u8; 25] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> [u8; 25] {
+        self.0
+    }
+
+    pub fn format(&self, style: KeyStyle) -> String {
+        // ? INFO: the alphabet was checked on construction, so formatting can't fail
+        codec::format_key(&self.0, style).unwrap()
+    }
+}
+
+impl fmt::Display for LicenseKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = match f.alternate() {
+            true => KeyStyle::CONTINUOUS,
+            false => KeyStyle::DASHED,
+        };
+
+        f.write_str(&self.format(style))
+    }
+}
+
+impl FromStr for LicenseKey {
+    type Err = KeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LicenseKey::new(s)
+    }
+}
+
+impl TryFrom<[u8; 25]> for LicenseKey {
+    type Error = KeyError;
+
+    fn try_from(value: [u8; 25]) -> Result<Self, Self::Error> {
+        LicenseKey::from_bytes(value)
+    }
+}
+
+impl TryFrom<&[u8; 25]> for LicenseKey {
+    type Error = KeyError;
+
+    fn try_from(value: &[u8; 25]) -> Result<Self, Self::Error> {
+        LicenseKey::from_bytes(*value)
+    }
+}
+
+impl From<LicenseKey> for [u8; 25] {
+    fn from(key: LicenseKey) -> [u8; 25] {
+        key.0
+    }
+}
+
+impl AsRef<[u8]> for LicenseKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl PartialEq<[u8; 25]> for LicenseKey {
+    fn eq(&self, other: &[u8; 25]) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KeyEdition, License};
+
+    #[test]
+    fn validation() {
+        let key: LicenseKey = "3BH41-94ZD6-4KDT5-JDPUY-TBSN9".parse().unwrap();
+        assert_eq!(key, *b"3BH4194ZD64KDT5JDPUYTBSN9");
+        assert_eq!(key.to_string(), "3BH41-94ZD6-4KDT5-JDPUY-TBSN9");
+        assert_eq!(format!("{key:#}"), "3BH4194ZD64KDT5JDPUYTBSN9");
+        assert_eq!(LicenseKey::new(key.to_string()).unwrap(), key);
+
+        assert!(matches!(LicenseKey::new("ABC"), Err(KeyError::InvalidLength { .. })));
+        assert!(matches!(
+            LicenseKey::from_bytes(*b"3BH4194ZD64KDT5JDPUYTBSN2"),
+            Err(KeyError::InvalidChecksum { .. })
+        ));
+        assert!(matches!(
+            LicenseKey::from_bytes(*b"0BH4194ZD64KDT5JDPUYTBSN9"),
+            Err(KeyError::InvalidCharacter { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn generated() {
+        let license = License::new(KeyEdition::Business).with_seats(8);
+        let key = license.generate();
+
+        assert_eq!(LicenseKey::from_bytes(key.into_bytes()).unwrap(), key);
+        assert_eq!(License::try_from(key).unwrap(), license);
+        assert_eq!(License::from_key(key.to_string()).unwrap(), license);
+    }
+
+    #[test]
+    fn arrays() {
+        let bytes = *b"3BH4194ZD64KDT5JDPUYTBSN9";
+        let key = LicenseKey::try_from(&bytes).unwrap();
+
+        assert_eq!(key.as_array(), &bytes);
+        assert_eq!(LicenseKey::try_from(key.as_array()).unwrap(), key);
+        assert_eq!(<[u8; 25]>::from(key), bytes);
+        assert!(LicenseKey::try_from(b"3BH4194ZD64KDT5JDPUYTBSN2").is_err());
+    }
+}
@@ -1,12 +1,68 @@
-use chrono::{Date, Datelike, Duration, TimeZone, Utc};
+//! Without the default `std` feature only the key alphabet, the [`codec`] and the [`checksum`]
+//! are available, they need nothing but `alloc`
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use chrono::NaiveDate;
 use core::convert::TryFrom;
 use core::fmt;
-use rand::{thread_rng, Rng};
-use std::ops::{Add, BitAnd, Mul, Shr};
-use std::string::String;
+use core::ops::RangeInclusive;
 use strum_macros::EnumIter;
 use thiserror::Error;
 
+#[cfg(feature = "std")]
+pub use crate::builder::{LicenseBuilder, NoEdition, WithEdition};
+pub use crate::checksum::{Checksum, ChecksumTrace};
+pub use crate::codec::{KeyStyle, Substitution};
+#[cfg(feature = "std")]
+pub use crate::key::LicenseKey;
+#[cfg(feature = "std")]
+pub use crate::license::{
+    random_base_pair, DateStep, DecodedKeyParts, Expiry, FieldChange, License, LossyLicense,
+    MaintenancePeriod,
+};
+#[cfg(feature = "std")]
+pub use crate::record::LicenseRecord;
+#[cfg(feature = "std")]
+pub use crate::scheme::KeyFormat;
+/// The `*_with_rng` methods take any [`rand::RngCore`], use this re-export to seed one without
+/// depending on a matching rand version
+#[cfg(feature = "std")]
+pub use rand;
+
+#[cfg(feature = "std")]
+mod builder;
+#[cfg(feature = "std")]
+pub mod bulk;
+pub mod checksum;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod codec;
+#[cfg(feature = "std")]
+pub mod compat;
+#[cfg(feature = "std")]
+mod key;
+#[cfg(feature = "std")]
+mod license;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "qr")]
+pub mod qr;
+#[cfg(feature = "std")]
+pub mod record;
+#[cfg(feature = "std")]
+pub mod scheme;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(any(all(test, feature = "std"), feature = "test-strategies"))]
+pub mod strategies;
+#[cfg(feature = "tracing")]
+pub mod telemetry;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
 const KEYS_SIZE: i32 = KEY_CHARS.len() as i32;
 const KEY_CHARS: [u8; 34] = [
     b'D', b'Y', b'1', b'4', b'U', b'F', b'3', b'R', b'H', b'W', b'C', b'X', b'L', b'Q', b'B', b'6',
@@ -14,21 +70,47 @@ const KEY_CHARS: [u8; 34] = [
     b'7', b'E',
 ];
 
+/// Seat counts accepted by both the builder and [`License::is_valid_key`]
+pub const SEATS_RANGE: RangeInclusive<i32> = 1..=797;
+pub const UNK1_RANGE: RangeInclusive<i32> = 99..=989;
+pub const UNK2_RANGE: RangeInclusive<i32> = 0..=100;
+pub const UNK3_RANGE: RangeInclusive<i32> = 0..=100;
+
 #[derive(Error, Debug)]
 pub enum KeyError {
     #[error("key has an invalid checksum")]
     InvalidChecksum { expected: u16, found: u16 },
     #[error("key has an invalid length")]
     InvalidLength { expected: usize, found: usize },
+    /// `index` is the raw edition index decoded from a key, `None` for an unknown edition name
     #[error("key belongs to an unknown edition")]
-    UnknownEdition,
+    UnknownEdition { index: Option<i32> },
+    #[error("key contains an invalid date")]
+    InvalidDate,
+    #[error("date is out of range")]
+    DateOutOfRange { min: NaiveDate, max: NaiveDate, found: NaiveDate },
+    #[error("value is out of range")]
+    ValueOutOfRange { min: i32, max: i32, found: i32 },
+    /// `index` is the position in the key with separators removed
+    #[error("character '{character}' at position {index} is not part of the key alphabet")]
+    InvalidCharacter { index: usize, character: char },
+    #[error("decoded {field} does not match the license")]
+    FieldMismatch { field: &'static str },
+    #[error("license is not valid")]
+    InvalidLicense,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumIter)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum KeyEdition {
     Business = 0,
     Extreme = 1,
     Engineer = 2,
+    // ? INFO: same names as the slugs, plus the aliases the FromStr impl accepts
+    #[cfg_attr(feature = "cli", value(name = "network", alias = "networkaudit", alias = "na"))]
+    #[cfg_attr(feature = "serde", serde(rename = "network"))]
     NetworkAudit = 3,
 }
 
@@ -52,284 +134,108 @@ impl TryFrom<i32> for KeyEdition {
             1 => Ok(KeyEdition::Extreme),
             2 => Ok(KeyEdition::Engineer),
             3 => Ok(KeyEdition::NetworkAudit),
-            _ => Err(KeyError::UnknownEdition),
+            index => Err(KeyError::UnknownEdition { index: Some(index) }),
         }
     }
 }
 
+/// Exact slugs only, see the [`FromStr`](core::str::FromStr) impl for lenient parsing
 impl TryFrom<&str> for KeyEdition {
     type Error = KeyError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "business" => Ok(KeyEdition::Business),
-            "extreme" => Ok(KeyEdition::Extreme),
-            "engineer" => Ok(KeyEdition::Engineer),
-            "network" => Ok(KeyEdition::NetworkAudit),
-            _ => Err(KeyError::UnknownEdition),
-        }
+        KeyEdition::all()
+            .into_iter()
+            .find(|edition| edition.slug() == value)
+            .ok_or(KeyError::UnknownEdition { index: None })
     }
 }
 
-trait DateExt {
-    fn enc(&self) -> i32;
-    fn dec(val: i32) -> Date<Utc>;
-}
+/// Case insensitive, accepts the slugs, the display names with or without spaces and `na` for
+/// network audit
+impl core::str::FromStr for KeyEdition {
+    type Err = KeyError;
 
-impl DateExt for Date<Utc> {
-    fn enc(&self) -> i32 {
-        let year = self.year().clamp(2004, 2099) - 2003;
-        let month = self.month().clamp(1, 12);
-        let day = self.day().clamp(1, 31);
-        year.mul(512).add(month.mul(32).add(day) as i32)
-    }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const ALIASES: [(&str, KeyEdition); 3] = [
+            ("network audit", KeyEdition::NetworkAudit),
+            ("networkaudit", KeyEdition::NetworkAudit),
+            ("na", KeyEdition::NetworkAudit),
+        ];
 
-    fn dec(val: i32) -> Date<Utc> {
-        let day = val.bitand(31) as u32;
-        let month = val.shr(5u32).bitand(15) as u32;
-        let year = val.shr(9u32).bitand(31).add(2003);
-        Utc.ymd(year, month, day)
+        let s = s.trim();
+        KeyEdition::all()
+            .into_iter()
+            .map(|edition| (edition.slug(), edition))
+            .chain(ALIASES)
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|(_, edition)| edition)
+            .ok_or(KeyError::UnknownEdition { index: None })
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct License {
-    pub edition: KeyEdition,
-    pub seats: i32,
-    pub purchase_date: Date<Utc>,
-    pub expiry: Option<Duration>,
-    pub maintenance_expiry: Duration,
-
-    unk1: i32,
-    unk2: i32,
-    unk3: i32,
-}
-
-impl License {
-    pub fn new(edition: KeyEdition) -> License {
-        let mut rng = thread_rng();
-
-        let unk1: i32 = rng.gen_range(100, 989);
-        let unk2: i32 = rng.gen_range(0, 100);
-        let unk3: i32 = rng.gen_range(0, 100);
-
-        License {
-            edition,
-            purchase_date: Utc::today(),
-            expiry: None,
-            seats: 1,
-            maintenance_expiry: Duration::days(3658),
-
-            unk1,
-            unk2,
-            unk3,
-        }
-    }
-
-    pub fn with_purchase_date(mut self, date: Date<Utc>) -> Self {
-        let date_2004 = Utc.ymd(2004, 1, 1);
-        let date_2099 = Utc.ymd(2099, 1, 1);
-        self.purchase_date = date.clamp(date_2004, date_2099);
-        self
+impl KeyEdition {
+    /// Every edition in index order
+    pub const fn all() -> [KeyEdition; 4] {
+        [KeyEdition::Business, KeyEdition::Extreme, KeyEdition::Engineer, KeyEdition::NetworkAudit]
     }
 
-    pub fn with_edition(mut self, edition: KeyEdition) -> Self {
-        self.edition = edition;
-        self
-    }
-
-    pub fn with_seats(mut self, seats: i32) -> Self {
-        self.seats = seats.clamp(1, 797);
-        self
-    }
-
-    pub fn with_license_expiry(mut self, duration: Option<Duration>) -> Self {
-        self.expiry = duration;
-        self
-    }
-
-    pub fn with_maintenance_expiry(mut self, duration: Duration) -> Self {
-        self.maintenance_expiry = duration.clamp(Duration::days(1), Duration::days(3658));
-        self
-    }
-
-    pub fn from_key<T: AsRef<[u8]>>(key: T) -> Result<License, KeyError> {
-        let key =
-            key.as_ref().iter().filter(|b| b.is_ascii_alphanumeric()).copied().collect::<Vec<u8>>();
-
-        if key.len() != 25 {
-            return Err(KeyError::InvalidLength { expected: 25, found: key.len() });
-        }
-
-        if !verify_checksum(&key) {
-            return Err(KeyError::InvalidChecksum {
-                expected: get_checksum(&key[0..24]),
-                found: key.last().copied().unwrap() as u16,
-            });
+    /// Lowercase name used by the CLI and the serde representation
+    pub const fn slug(&self) -> &'static str {
+        match self {
+            KeyEdition::Business => "business",
+            KeyEdition::Extreme => "extreme",
+            KeyEdition::Engineer => "engineer",
+            KeyEdition::NetworkAudit => "network",
         }
-
-        let key_parts: [i32; 9] = [
-            dec_part(&key[0..2]),
-            dec_part(&key[2..4]),
-            dec_part(&key[4..6]),
-            dec_part(&key[6..8]),
-            dec_part(&key[8..12]),
-            dec_part(&key[12..16]),
-            dec_part(&key[16..19]),
-            dec_part(&key[19..22]),
-            dec_part(&key[22..24]),
-        ];
-
-        let edition = ((key_parts[8] & 0xFF) ^ key_parts[0] ^ 0xBF) - 1;
-        let edition = KeyEdition::try_from(edition)?;
-
-        let seats = key_parts[8] ^ key_parts[4] ^ 0x4755;
-        let purchase_date = Date::dec(key_parts[8] ^ key_parts[5] ^ 0x7CC1);
-
-        let expiry = (key_parts[8] & 0xFF) ^ key_parts[6] ^ 0x3FD;
-        let expiry = match expiry {
-            0 => None,
-            _ => Some(Date::dec(expiry) - purchase_date),
-        };
-
-        let maintenance_expiry = (key_parts[8] & 0xFF) ^ key_parts[7] ^ 0x935;
-        let maintenance_expiry = Duration::days(maintenance_expiry as i64);
-
-        let unk1 = (key_parts[8] & 0xFF) ^ key_parts[1] ^ 0xED;
-        let unk2 = (key_parts[8] & 0xFF) ^ (key_parts[2] & 0xFFFF) ^ 0x77;
-        let unk3 = (key_parts[8] & 0xFF) ^ (key_parts[3] & 0xFFFF) ^ 0xDF;
-
-        Ok(License { edition, seats, purchase_date, expiry, maintenance_expiry, unk1, unk2, unk3 })
     }
 
-    pub fn generate(&self) -> [u8; 25] {
-        let mut enc_key: [u8; 25] = [0; 25];
-        gen_pair(&mut enc_key[22..24]);
-
-        let purchase_date = self.purchase_date.enc();
-        let expiry = self.expiry.map(|exp| exp.num_days()).unwrap_or(0) as i32;
-        let maintenance_expiry = self.maintenance_expiry.num_days() as i32;
-
-        let base_val = dec_part(&mut enc_key[22..24]);
-        enc_part((base_val & 0xFF) ^ (self.edition as i32 + 1) ^ 0xBF, &mut enc_key[0..2]);
-        enc_part((base_val & 0xFF) ^ self.unk1 ^ 0xED, &mut enc_key[2..4]);
-        enc_part((base_val & 0xFF) ^ self.unk2 ^ 0x77, &mut enc_key[4..6]);
-        enc_part((base_val & 0xFF) ^ self.unk3 ^ 0xDF, &mut enc_key[6..8]);
-        enc_part((base_val & 0xFFFFFF) ^ self.seats ^ 0x4755, &mut enc_key[8..12]);
-        enc_part((base_val & 0xFFFFFF) ^ purchase_date ^ 0x7CC1, &mut enc_key[12..16]);
-        enc_part((base_val & 0xFF) ^ expiry ^ 0x3FD, &mut enc_key[16..19]);
-        enc_part((base_val & 0xFF) ^ maintenance_expiry ^ 0x935, &mut enc_key[19..22]);
-
-        let mut enc_checksum: [u8; 3] = [0; 3];
-        enc_part(get_checksum(&mut enc_key[0..24]) as i32, &mut enc_checksum);
-
-        enc_key[24] = enc_checksum[1];
-        enc_key
-    }
-
-    pub fn generate_string(&self, separators: bool) -> String {
-        let mut key = self.generate().to_vec();
-
-        if separators {
-            key.insert(20, b'-');
-            key.insert(15, b'-');
-            key.insert(10, b'-');
-            key.insert(5, b'-');
+    pub const fn description(&self) -> &'static str {
+        match self {
+            KeyEdition::Business => "Network management and remote support for businesses",
+            KeyEdition::Extreme => "System diagnostics and benchmarks for home users",
+            KeyEdition::Engineer => "System diagnostics and benchmarks for technicians",
+            KeyEdition::NetworkAudit => "Network inventory and audits for businesses",
         }
-
-        String::from_utf8(key).unwrap()
     }
 
-    pub fn is_valid_key(&self) -> bool {
-        let mut days_left = 0;
-
-        let date_2004 = Utc.ymd(2004, 1, 1);
-        let date_2099 = Utc.ymd(2099, 1, 1);
-
-        if (date_2004..=date_2099).contains(&self.purchase_date) {
-            let current_days = Utc::today().enc();
-            let purchase_days = self.purchase_date.enc();
-            let expiry_days = self.expiry.map(|exp| exp.num_days()).unwrap_or(0) as i32;
-            days_left = (expiry_days + purchase_days) - current_days
-        }
-
-        (self.expiry.is_none() || days_left > 0)
-            && (0..797).contains(&self.seats)
-            && (99..990).contains(&self.unk1)
-            && self.unk2 <= 100
-            && self.unk3 <= 100
-            && self.maintenance_expiry.num_days() < 3659
+    /// Most seats a key of this edition can encode, the same for every edition
+    pub const fn max_seats(&self) -> i32 {
+        *SEATS_RANGE.end()
     }
 }
 
-fn gen_pair(slice: &mut [u8]) {
-    slice.iter_mut().for_each(|x| *x = KEY_CHARS[thread_rng().gen_range(0, KEYS_SIZE) as usize])
-}
-
-fn enc_part(mut val: i32, slice: &mut [u8]) {
-    slice.iter_mut().rev().for_each(|x| {
-        *x = KEY_CHARS[(val % KEYS_SIZE) as usize];
-        val /= KEYS_SIZE;
-    })
-}
-
-fn dec_part<T: AsRef<[u8]>>(key_part: T) -> i32 {
-    key_part.as_ref().iter().fold(0i32, |result, c1| {
-        (result * KEYS_SIZE) + KEY_CHARS.iter().position(|&c2| c2 == *c1).unwrap_or(0) as i32
-    })
-}
-
-fn get_checksum<T: AsRef<[u8]>>(key_part: T) -> u16 {
-    let checksum = (key_part.as_ref().iter().fold(0u32, |result, b| {
-        (0..8).fold(result ^ (*b as u32) << 8, |result, _| {
-            if result & 0x8000 == 0 {
-                result << 1
-            } else {
-                result << 1 ^ 0x8201
-            }
-        })
-    }) & 0xFFFF) as u16;
-
-    checksum % 0x9987
-}
+pub fn parse_base_pair<T: AsRef<[u8]>>(pair: T) -> Result<[u8; 2], KeyError> {
+    let pair = pair.as_ref();
 
-fn verify_checksum<T: AsRef<[u8]>>(key: T) -> bool {
-    let key = key.as_ref();
-    key.len() == 25 && {
-        let mut enc_checksum: [u8; 3] = [0; 3];
-        enc_part(get_checksum(&key[0..24]) as i32, &mut enc_checksum);
-
-        enc_checksum[1] == key[24]
+    if pair.len() != 2 {
+        return Err(KeyError::InvalidLength { expected: 2, found: pair.len() });
     }
+
+    codec::check_alphabet(pair)?;
+    Ok([pair[0], pair[1]])
 }
 
 #[cfg(test)]
 mod tests {
-    use strum::IntoEnumIterator;
-
     use super::*;
 
     #[test]
-    fn parse_license() {
-        assert!(
-            License::from_key("  3BH41-94ZD6 4KDT5JD-PUY_TBSN9 ").unwrap().is_valid_key(),
-            "parsed valid license as invalid!"
-        );
-
-        assert!(
-            License::from_key("  3BH41-94ZD6 4KDT5JD-PUY_TBSN2 ").is_err(),
-            "parsed license did not trip the checksum check!"
-        );
-    }
+    fn edition_names() {
+        for edition in KeyEdition::all() {
+            assert_eq!(KeyEdition::try_from(edition.slug()).unwrap(), edition);
+            assert_eq!(edition.slug().to_uppercase().parse::<KeyEdition>().unwrap(), edition);
+        }
 
-    #[test]
-    fn generate() {
-        for edition in KeyEdition::iter() {
-            assert!(License::new(edition).is_valid_key(), "generated invalid license!");
-            assert!(
-                License::new(edition).with_license_expiry(Some(Duration::days(50))).is_valid_key(),
-                "generated invalid license when using an expiry!"
-            );
+        for alias in ["NetworkAudit", "network audit", " NA "] {
+            assert_eq!(alias.parse::<KeyEdition>().unwrap(), KeyEdition::NetworkAudit);
         }
+
+        assert!(KeyEdition::try_from("Extreme").is_err());
+        assert!("ultimate".parse::<KeyEdition>().is_err());
+        assert!(matches!(
+            KeyEdition::try_from(7),
+            Err(KeyError::UnknownEdition { index: Some(7) })
+        ));
     }
 }
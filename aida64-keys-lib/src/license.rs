@@ -0,0 +1,1770 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, Utc};
+use core::fmt;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng, RngCore, SeedableRng};
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::io::BufRead;
+use std::iter;
+use std::ops::{Add, BitAnd, Mul, RangeInclusive, Shr};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::codec::{check_alphabet, collect_key, digit, KeyStyle, Substitution};
+use crate::compat::IntoNaiveDate;
+use crate::key::LicenseKey;
+use crate::scheme::{self, Aida64, KeyFormat, KeyScheme};
+#[cfg(feature = "tracing")]
+use crate::telemetry;
+use crate::{bulk, checksum, codec};
+use crate::{
+    parse_base_pair, KeyEdition, KeyError, KEY_CHARS, SEATS_RANGE, UNK1_RANGE, UNK2_RANGE,
+    UNK3_RANGE,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter)]
+pub enum DateStep {
+    Day,
+    Week,
+    Month,
+}
+
+impl DateStep {
+    /// Returns the date `n` steps after `start`, always stepping from `start` so month-end
+    /// dates don't drift (Jan 31 -> Feb 28 -> Mar 31)
+    pub fn nth(&self, start: NaiveDate, n: u32) -> Option<NaiveDate> {
+        match self {
+            DateStep::Day => start.checked_add_signed(Duration::days(n as i64)),
+            DateStep::Week => start.checked_add_signed(Duration::weeks(n as i64)),
+            DateStep::Month => start.checked_add_months(Months::new(n)),
+        }
+    }
+}
+
+impl fmt::Display for DateStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DateStep::Day => write!(f, "Daily"),
+            DateStep::Week => write!(f, "Weekly"),
+            DateStep::Month => write!(f, "Monthly"),
+        }
+    }
+}
+
+/// Policy-style license expiries, computed relative to the purchase date
+pub struct Expiry;
+
+impl Expiry {
+    /// Expires on the last day of the purchase month, or of the following month when the license
+    /// is purchased on a month's last day
+    pub fn end_of_month(purchase: NaiveDate) -> Duration {
+        let end = last_day_of_month(purchase);
+        match end == purchase {
+            true => last_day_of_month(purchase + Duration::days(1)) - purchase,
+            false => end - purchase,
+        }
+    }
+
+    /// Expires on December 31st of the purchase year, or of the following year when the license
+    /// is purchased on December 31st
+    pub fn calendar_year_end(purchase: NaiveDate) -> Duration {
+        let end = NaiveDate::from_ymd(purchase.year(), 12, 31);
+        match end == purchase {
+            true => NaiveDate::from_ymd(purchase.year() + 1, 12, 31) - purchase,
+            false => end - purchase,
+        }
+    }
+
+    /// Expires on the same day of the month `n` months after the purchase
+    pub fn months(purchase: NaiveDate, n: u32) -> Duration {
+        DateStep::Month.nth(purchase, n).map(|end| end - purchase).unwrap_or_else(Duration::zero)
+    }
+}
+
+/// Policy-style maintenance periods, computed relative to the purchase date
+pub struct MaintenancePeriod;
+
+impl MaintenancePeriod {
+    /// Longest maintenance period a key can encode
+    pub fn max() -> Duration {
+        Duration::days(3658)
+    }
+
+    /// Maintenance ending on the anniversary of the purchase `n` years later
+    pub fn years(purchase: NaiveDate, n: u32) -> Duration {
+        Expiry::months(purchase, n.saturating_mul(12)).min(Self::max())
+    }
+}
+
+fn last_day_of_month(date: NaiveDate) -> NaiveDate {
+    let (year, month) = match date.month() {
+        12 => (date.year() + 1, 1),
+        month => (date.year(), month + 1),
+    };
+    NaiveDate::from_ymd(year, month, 1).pred()
+}
+
+/// Earliest and latest purchase dates a key can encode, the year is stored in 5 bits from 2003
+pub(crate) fn purchase_date_range() -> RangeInclusive<NaiveDate> {
+    NaiveDate::from_ymd(2004, 1, 1)..=NaiveDate::from_ymd(2034, 12, 31)
+}
+
+fn today() -> NaiveDate {
+    Utc::today().naive_utc()
+}
+
+trait DateExt: Sized {
+    fn enc(&self) -> i32;
+    fn dec(val: i32) -> Option<Self>;
+}
+
+impl DateExt for NaiveDate {
+    fn enc(&self) -> i32 {
+        let year = self.year().clamp(2004, 2034) - 2003;
+        let month = self.month().clamp(1, 12);
+        let day = self.day().clamp(1, 31);
+        year.mul(512).add(month.mul(32).add(day) as i32)
+    }
+
+    fn dec(val: i32) -> Option<NaiveDate> {
+        let day = val.bitand(31) as u32;
+        let month = val.shr(5u32).bitand(15) as u32;
+        let year = val.shr(9u32).bitand(31).add(2003);
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+}
+
+/// The fields are only set through the `with_*` methods, which keep them in the ranges a key can
+/// encode, licenses decoded from a key keep whatever the key contains.
+///
+/// With the `serde` feature enabled, licenses (de)serialize with the purchase date as
+/// `YYYY-MM-DD` and the expiries as day counts, missing components are randomized
+///
+/// Equality and hashing only look at the entitlement (edition, seats and dates), so keys that
+/// differ in the order id, custom values or base pair compare equal, see [`License::identical`]
+#[derive(Debug, Clone)]
+pub struct License {
+    edition: KeyEdition,
+    seats: i32,
+    purchase_date: NaiveDate,
+    expiry: Option<Duration>,
+    maintenance_expiry: Duration,
+
+    unk1: i32,
+    unk2: i32,
+    unk3: i32,
+    base: Option<[u8; 2]>,
+}
+
+/// Raw key segments as decoded from the alphabet, along with the values they unmask to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodedKeyParts {
+    /// Edition, the three components, seats, purchase date, expiry, maintenance and base
+    pub segments: [i32; 9],
+    /// Value of the two base characters, its low byte masks most other segments
+    pub base: i32,
+    /// Every segment but the base with its XOR mask undone, the edition is stored one higher and
+    /// the purchase date in its encoded form
+    pub unmasked: [i32; 8],
+    /// The trailing check character
+    pub checksum: u8,
+}
+
+/// Fields of a key decoded without mapping them to a [`License`], see [`License::from_key_lossy`]
+#[derive(Debug)]
+pub struct LossyLicense {
+    /// Raw edition index, [`KeyEdition::try_from`] maps it if it's known
+    pub edition: i32,
+    pub seats: i32,
+    /// `None` if the encoded date doesn't exist
+    pub purchase_date: Option<NaiveDate>,
+    pub expiry: Option<Duration>,
+    pub maintenance_expiry: Duration,
+    /// Order id and the two custom values, see [`License::components`]
+    pub components: [i32; 3],
+    /// Everything [`License::from_key`] would reject, with the name of the affected field or
+    /// `"key"` for invalid characters and the checksum
+    pub problems: Vec<(&'static str, KeyError)>,
+}
+
+impl LossyLicense {
+    fn decode<S: KeyScheme>(key: &[u8; 25]) -> LossyLicense {
+        let [edition, unk1, unk2, unk3, seats, purchase_date, expiry, maintenance_expiry] =
+            scheme::unmask::<S>(key);
+
+        LossyLicense {
+            edition: edition - 1,
+            seats,
+            purchase_date: NaiveDate::dec(purchase_date),
+            expiry: match expiry {
+                0 => None,
+                _ => Some(Duration::days(expiry as i64)),
+            },
+            maintenance_expiry: Duration::days(maintenance_expiry as i64),
+            components: [unk1, unk2, unk3],
+            problems: Vec::new(),
+        }
+    }
+
+    fn check_fields(&mut self) {
+        let max_days = MaintenancePeriod::max().num_days() as i32;
+        let days = |duration: Duration| duration.num_days() as i32;
+
+        if let Err(e) = KeyEdition::try_from(self.edition) {
+            self.problems.push(("edition", e));
+        }
+
+        let ranges = [
+            ("seats", self.seats, SEATS_RANGE),
+            ("expiry", self.expiry.map_or(1, days), 1..=max_days),
+            ("maintenance_expiry", days(self.maintenance_expiry), 1..=max_days),
+            ("order_id", self.components[0], UNK1_RANGE),
+            ("custom_a", self.components[1], UNK2_RANGE),
+            ("custom_b", self.components[2], UNK3_RANGE),
+        ];
+        for (field, value, range) in ranges {
+            if let Err(e) = check_range(value, range) {
+                self.problems.push((field, e));
+            }
+        }
+
+        match self.purchase_date {
+            None => self.problems.push(("purchase_date", KeyError::InvalidDate)),
+            Some(date) => {
+                let range = purchase_date_range();
+                if !range.contains(&date) {
+                    let (min, max) = range.into_inner();
+                    let e = KeyError::DateOutOfRange { min, max, found: date };
+                    self.problems.push(("purchase_date", e));
+                }
+            },
+        }
+    }
+
+    /// The edition, `None` if the index doesn't map to a known one
+    pub fn known_edition(&self) -> Option<KeyEdition> {
+        KeyEdition::try_from(self.edition).ok()
+    }
+}
+
+/// A field that differs between two licenses, see [`License::diff`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldChange {
+    Edition {
+        from: KeyEdition,
+        to: KeyEdition,
+    },
+    Seats {
+        from: i32,
+        to: i32,
+    },
+    PurchaseDate {
+        from: NaiveDate,
+        to: NaiveDate,
+    },
+    /// Expiry dates, `None` if the license never expires
+    Expiry {
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    },
+    MaintenanceExpiry {
+        from: NaiveDate,
+        to: NaiveDate,
+    },
+}
+
+impl FieldChange {
+    /// Name of the field, the same one [`KeyError::FieldMismatch`] reports
+    pub fn field(&self) -> &'static str {
+        match self {
+            FieldChange::Edition { .. } => "edition",
+            FieldChange::Seats { .. } => "seats",
+            FieldChange::PurchaseDate { .. } => "purchase date",
+            FieldChange::Expiry { .. } => "expiry",
+            FieldChange::MaintenanceExpiry { .. } => "maintenance expiry",
+        }
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let never = |date: Option<NaiveDate>| date.map_or("never".to_string(), |d| d.to_string());
+
+        match self {
+            FieldChange::Edition { from, to } => write!(f, "edition: {from} -> {to}"),
+            FieldChange::Seats { from, to } => write!(f, "seats: {from} -> {to}"),
+            FieldChange::PurchaseDate { from, to } => write!(f, "purchase date: {from} -> {to}"),
+            FieldChange::Expiry { from, to } => {
+                write!(f, "expiry: {} -> {}", never(*from), never(*to))
+            },
+            FieldChange::MaintenanceExpiry { from, to } => {
+                write!(f, "maintenance expiry: {from} -> {to}")
+            },
+        }
+    }
+}
+
+impl License {
+    pub fn new(edition: KeyEdition) -> License {
+        License::new_with_rng(edition, &mut thread_rng())
+    }
+
+    /// A single seat license that expires after `days` (clamped like
+    /// [`License::with_license_expiry`]), with maintenance ending on the same day
+    pub fn trial(edition: KeyEdition, days: i64) -> License {
+        let license = License::new(edition).with_license_expiry(Some(Duration::days(days)));
+        let expiry = license.expiry.unwrap_or_else(MaintenancePeriod::max);
+        license.with_maintenance_expiry(expiry)
+    }
+
+    /// A single seat license that never expires, with the longest possible maintenance
+    pub fn lifetime(edition: KeyEdition) -> License {
+        License::new(edition)
+            .with_license_expiry(None)
+            .with_maintenance_expiry(MaintenancePeriod::max())
+    }
+
+    /// Same as [`License::lifetime`] for `seats` seats, clamped like [`License::with_seats`]
+    pub fn site(edition: KeyEdition, seats: i32) -> License {
+        License::lifetime(edition).with_seats(seats)
+    }
+
+    /// Same as [`License::new`] but draws the random components from `rng`
+    pub fn new_with_rng<R: RngCore + ?Sized>(edition: KeyEdition, rng: &mut R) -> License {
+        License {
+            edition,
+            purchase_date: today(),
+            expiry: None,
+            seats: 1,
+            maintenance_expiry: MaintenancePeriod::max(),
+
+            unk1: 0,
+            unk2: 0,
+            unk3: 0,
+            base: None,
+        }
+        .rerolled(rng)
+    }
+
+    /// Copy of this license with new random components and a random base pair
+    pub(crate) fn rerolled<R: RngCore + ?Sized>(&self, rng: &mut R) -> License {
+        License {
+            unk1: rng.gen_range(100..989),
+            unk2: rng.gen_range(0..100),
+            unk3: rng.gen_range(0..100),
+            base: None,
+            ..self.clone()
+        }
+    }
+
+    /// Draws new random components and clears a fixed base pair, so the same entitlement encodes
+    /// to keys that share nothing with the previous ones
+    pub fn reroll_random_fields(&mut self) {
+        self.reroll_random_fields_with_rng(&mut thread_rng())
+    }
+
+    /// Same as [`License::reroll_random_fields`] but draws from `rng`
+    pub fn reroll_random_fields_with_rng<R: RngCore + ?Sized>(&mut self, rng: &mut R) {
+        *self = self.rerolled(rng);
+    }
+
+    /// Fixes a new random base pair, different from the current one if one is fixed, so
+    /// [`License::generate`] keeps returning the same key until the next reroll
+    pub fn reroll_base_pair(&mut self) {
+        self.reroll_base_pair_with_rng(&mut thread_rng())
+    }
+
+    /// Same as [`License::reroll_base_pair`] but draws from `rng`
+    pub fn reroll_base_pair_with_rng<R: RngCore + ?Sized>(&mut self, rng: &mut R) {
+        let pair = loop {
+            let pair = scheme::random_pair::<Aida64, _>(rng);
+            if self.base != Some(pair) {
+                break pair;
+            }
+        };
+
+        self.base = Some(pair);
+    }
+
+    pub fn with_purchase_date<D: IntoNaiveDate>(mut self, date: D) -> Self {
+        let range = purchase_date_range();
+        self.purchase_date = date.into_naive_date().clamp(*range.start(), *range.end());
+        self
+    }
+
+    pub fn with_edition(mut self, edition: KeyEdition) -> Self {
+        self.edition = edition;
+        self
+    }
+
+    pub fn with_seats(mut self, seats: i32) -> Self {
+        self.seats = seats.clamp(*SEATS_RANGE.start(), *SEATS_RANGE.end());
+        self
+    }
+
+    /// The expiry is clamped to the same range as the maintenance expiry
+    pub fn with_license_expiry(mut self, duration: Option<Duration>) -> Self {
+        self.expiry =
+            duration.map(|duration| duration.clamp(Duration::days(1), MaintenancePeriod::max()));
+        self
+    }
+
+    pub fn with_maintenance_expiry(mut self, duration: Duration) -> Self {
+        self.maintenance_expiry = duration.clamp(Duration::days(1), MaintenancePeriod::max());
+        self
+    }
+
+    /// Whether every field matches, including the order id, custom values and base pair
+    pub fn identical(&self, other: &License) -> bool {
+        self == other && self.components() == other.components() && self.base == other.base
+    }
+
+    fn entitlement(&self) -> (KeyEdition, i32, NaiveDate, Option<Duration>, Duration) {
+        (self.edition, self.seats, self.purchase_date, self.expiry, self.maintenance_expiry)
+    }
+
+    /// One line description like `Extreme edition, 5 seats, purchased 2024-03-01, never expires,
+    /// maintenance until 2034-03-04`
+    pub fn summary(&self) -> String {
+        let seats = match self.seats {
+            1 => "1 seat".to_string(),
+            seats => format!("{seats} seats"),
+        };
+        let expiry = match self.expiry_date() {
+            Some(date) => format!("expires {date}"),
+            None => "never expires".to_string(),
+        };
+
+        format!(
+            "{} edition, {seats}, purchased {}, {expiry}, maintenance until {}",
+            self.edition,
+            self.purchase_date,
+            self.maintenance_expiry_date()
+        )
+    }
+
+    /// Every entitlement field that differs from `self` to `other`, the order id, custom values and
+    /// base pair aren't compared
+    pub fn diff(&self, other: &License) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+
+        if self.edition != other.edition {
+            changes.push(FieldChange::Edition { from: self.edition, to: other.edition });
+        }
+        if self.seats != other.seats {
+            changes.push(FieldChange::Seats { from: self.seats, to: other.seats });
+        }
+        if self.purchase_date != other.purchase_date {
+            let (from, to) = (self.purchase_date, other.purchase_date);
+            changes.push(FieldChange::PurchaseDate { from, to });
+        }
+        if self.expiry_date() != other.expiry_date() {
+            changes.push(FieldChange::Expiry { from: self.expiry_date(), to: other.expiry_date() });
+        }
+        if self.maintenance_expiry_date() != other.maintenance_expiry_date() {
+            let (from, to) = (self.maintenance_expiry_date(), other.maintenance_expiry_date());
+            changes.push(FieldChange::MaintenanceExpiry { from, to });
+        }
+
+        changes
+    }
+
+    /// Adds `extra` to the maintenance period, clamped like [`License::with_maintenance_expiry`]
+    pub fn extend_maintenance(&mut self, extra: Duration) {
+        self.maintenance_expiry =
+            (self.maintenance_expiry + extra).clamp(Duration::days(1), MaintenancePeriod::max());
+    }
+
+    /// Moves the purchase date to `new_purchase_date`, clamped like
+    /// [`License::with_purchase_date`]. The expiry and maintenance periods restart from the new
+    /// date, the order id and custom values are kept so the renewed key traces back to this one.
+    pub fn renew<D: IntoNaiveDate>(&mut self, new_purchase_date: D) {
+        let range = purchase_date_range();
+        self.purchase_date =
+            new_purchase_date.into_naive_date().clamp(*range.start(), *range.end());
+    }
+
+    /// Like [`License::with_purchase_date`], but errors instead of clamping out of range dates
+    pub fn try_with_purchase_date<D: IntoNaiveDate>(mut self, date: D) -> Result<Self, KeyError> {
+        let date = date.into_naive_date();
+        let range = purchase_date_range();
+
+        if !range.contains(&date) {
+            return Err(KeyError::DateOutOfRange {
+                min: *range.start(),
+                max: *range.end(),
+                found: date,
+            });
+        }
+
+        self.purchase_date = date;
+        Ok(self)
+    }
+
+    /// Like [`License::with_seats`], but errors instead of clamping out of range seat counts
+    pub fn try_with_seats(mut self, seats: i32) -> Result<Self, KeyError> {
+        self.seats = check_range(seats, SEATS_RANGE)?;
+        Ok(self)
+    }
+
+    /// Like [`License::with_license_expiry`], but errors instead of clamping, the range is reported
+    /// in days
+    pub fn try_with_license_expiry(mut self, duration: Option<Duration>) -> Result<Self, KeyError> {
+        if let Some(duration) = duration {
+            let max = MaintenancePeriod::max().num_days() as i32;
+            let days = i32::try_from(duration.num_days()).unwrap_or(i32::MAX);
+
+            if !(1..=max).contains(&days) {
+                return Err(KeyError::ValueOutOfRange { min: 1, max, found: days });
+            }
+        }
+
+        self.expiry = duration;
+        Ok(self)
+    }
+
+    /// Like [`License::with_maintenance_expiry`], but errors instead of clamping, the range is
+    /// reported in days
+    pub fn try_with_maintenance_expiry(mut self, duration: Duration) -> Result<Self, KeyError> {
+        let max = MaintenancePeriod::max().num_days() as i32;
+        let days = i32::try_from(duration.num_days()).unwrap_or(i32::MAX);
+
+        if !(1..=max).contains(&days) {
+            return Err(KeyError::ValueOutOfRange { min: 1, max, found: days });
+        }
+
+        self.maintenance_expiry = duration;
+        Ok(self)
+    }
+
+    pub fn edition(&self) -> KeyEdition {
+        self.edition
+    }
+
+    pub fn seats(&self) -> i32 {
+        self.seats
+    }
+
+    pub fn purchase_date(&self) -> NaiveDate {
+        self.purchase_date
+    }
+
+    /// Days from the purchase until the license expires, `None` if it never does
+    pub fn expiry(&self) -> Option<Duration> {
+        self.expiry
+    }
+
+    /// Days from the purchase until the maintenance expires
+    pub fn maintenance_expiry(&self) -> Duration {
+        self.maintenance_expiry
+    }
+
+    /// The three embedded components that are randomized on creation
+    pub fn components(&self) -> [i32; 3] {
+        [self.unk1, self.unk2, self.unk3]
+    }
+
+    pub fn with_components(mut self, components: [i32; 3]) -> Result<Self, KeyError> {
+        for (value, range) in components.iter().zip([UNK1_RANGE, UNK2_RANGE, UNK3_RANGE]) {
+            check_range(*value, range)?;
+        }
+
+        [self.unk1, self.unk2, self.unk3] = components;
+        Ok(self)
+    }
+
+    /// First embedded component, free to carry a caller chosen id within [`UNK1_RANGE`] that
+    /// survives a generate/parse round trip
+    pub fn order_id(&self) -> i32 {
+        self.unk1
+    }
+
+    /// Second embedded component, within [`UNK2_RANGE`]
+    pub fn custom_a(&self) -> i32 {
+        self.unk2
+    }
+
+    /// Third embedded component, within [`UNK3_RANGE`]
+    pub fn custom_b(&self) -> i32 {
+        self.unk3
+    }
+
+    pub fn with_order_id(mut self, order_id: i32) -> Result<Self, KeyError> {
+        self.unk1 = check_range(order_id, UNK1_RANGE)?;
+        Ok(self)
+    }
+
+    pub fn with_custom_a(mut self, value: i32) -> Result<Self, KeyError> {
+        self.unk2 = check_range(value, UNK2_RANGE)?;
+        Ok(self)
+    }
+
+    pub fn with_custom_b(mut self, value: i32) -> Result<Self, KeyError> {
+        self.unk3 = check_range(value, UNK3_RANGE)?;
+        Ok(self)
+    }
+
+    /// Fixes the two base characters every other part of the key is masked with, `None` picks a
+    /// random pair on every call to [`License::generate`]
+    pub fn with_base_pair(mut self, pair: Option<[u8; 2]>) -> Result<Self, KeyError> {
+        self.base = pair.map(parse_base_pair).transpose()?;
+        Ok(self)
+    }
+
+    /// The fixed base pair, licenses decoded from a key always have the pair of that key. Pairs
+    /// are kept in the [`Aida64`] alphabet, also for keys of another [`KeyScheme`].
+    pub fn base_pair(&self) -> Option<[u8; 2]> {
+        self.base
+    }
+
+    /// Sets the license expiry to an absolute date, relative to the purchase date configured at
+    /// the time of the call, so the purchase date has to be set first
+    pub fn with_expiry_date<D: IntoNaiveDate>(mut self, date: D) -> Result<Self, KeyError> {
+        self.expiry = Some(self.offset_from_purchase(date.into_naive_date())?);
+        Ok(self)
+    }
+
+    /// Sets the maintenance expiry to an absolute date, relative to the purchase date configured
+    /// at the time of the call, so the purchase date has to be set first
+    pub fn with_maintenance_expiry_date<D: IntoNaiveDate>(
+        mut self,
+        date: D,
+    ) -> Result<Self, KeyError> {
+        self.maintenance_expiry = self.offset_from_purchase(date.into_naive_date())?;
+        Ok(self)
+    }
+
+    /// The day the license expires, `None` if it never does
+    pub fn expiry_date(&self) -> Option<NaiveDate> {
+        self.expiry.map(|expiry| self.purchase_date + expiry)
+    }
+
+    pub fn maintenance_expiry_date(&self) -> NaiveDate {
+        self.purchase_date + self.maintenance_expiry
+    }
+
+    /// Days until the license expires as of `asof`, 0 once it has, `None` if it never expires
+    pub fn remaining_days<D: IntoNaiveDate>(&self, asof: D) -> Option<i64> {
+        let asof = asof.into_naive_date();
+        self.expiry_date().map(|expiry| (expiry - asof).num_days().max(0))
+    }
+
+    /// Whether the license has expired as of `asof`, licenses expire at the start of their expiry
+    /// date
+    pub fn is_expired<D: IntoNaiveDate>(&self, asof: D) -> bool {
+        self.remaining_days(asof) == Some(0)
+    }
+
+    /// Days until the maintenance expires as of `asof`, 0 once it has
+    pub fn maintenance_remaining<D: IntoNaiveDate>(&self, asof: D) -> i64 {
+        (self.maintenance_expiry_date() - asof.into_naive_date()).num_days().max(0)
+    }
+
+    pub fn maintenance_active<D: IntoNaiveDate>(&self, asof: D) -> bool {
+        self.maintenance_remaining(asof) > 0
+    }
+
+    fn offset_from_purchase(&self, date: NaiveDate) -> Result<Duration, KeyError> {
+        let min = self.purchase_date + Duration::days(1);
+        let max = self.purchase_date + MaintenancePeriod::max();
+
+        if !(min..=max).contains(&date) {
+            return Err(KeyError::DateOutOfRange { min, max, found: date });
+        }
+
+        Ok(date - self.purchase_date)
+    }
+
+    /// Yields a copy of this license for every `step` between the start and end of `range`
+    /// (inclusive), with only the purchase date changed
+    pub fn sweep_dates(
+        &self,
+        range: RangeInclusive<NaiveDate>,
+        step: DateStep,
+    ) -> impl Iterator<Item = License> + '_ {
+        let (start, end) = range.into_inner();
+        (0..)
+            .map_while(move |n| step.nth(start, n))
+            .take_while(move |date| *date <= end)
+            .map(|date| self.clone().with_purchase_date(date))
+    }
+
+    /// Splits `key` into its raw and unmasked segments without verifying the checksum or any field
+    pub fn decode_parts<T: AsRef<[u8]>>(key: T) -> Result<DecodedKeyParts, KeyError> {
+        let key = normalize_key(key)?;
+        let segments = scheme::segments::<Aida64>(&key);
+        let unmasked = scheme::unmask::<Aida64>(&key);
+
+        Ok(DecodedKeyParts { segments, base: segments[8], unmasked, checksum: key[24] })
+    }
+
+    pub fn from_key<T: AsRef<[u8]>>(key: T) -> Result<License, KeyError> {
+        License::from_key_bytes(&normalize_key(key)?)
+    }
+
+    /// Same as [`License::from_key`] for a key without separators, decodes without allocating
+    pub fn from_key_bytes(key: &[u8; 25]) -> Result<License, KeyError> {
+        License::decode::<Aida64>(key)
+    }
+
+    /// Same as [`License::from_key`] for keys of another [`KeyScheme`]
+    pub fn from_key_with_scheme<S: KeyScheme, T: AsRef<[u8]>>(key: T) -> Result<License, KeyError> {
+        License::decode::<S>(&collect_key(key)?)
+    }
+
+    /// Same as [`License::from_key`] for keys of `format`
+    pub fn from_key_format<T: AsRef<[u8]>>(key: T, format: KeyFormat) -> Result<License, KeyError> {
+        License::decode_format(&collect_key(key)?, format)
+    }
+
+    /// Tries every [`KeyFormat`] in turn and returns the first that decodes `key`, along with the
+    /// license. Fails with the error of the default format when none does.
+    ///
+    /// A placeholder until a second format is known: with [`KeyFormat::V6`] as the only variant
+    /// this is [`License::from_key`] that also returns `V6`.
+    pub fn from_key_any<T: AsRef<[u8]>>(key: T) -> Result<(License, KeyFormat), KeyError> {
+        let key = collect_key(key)?;
+        let mut first_error = None;
+
+        for format in KeyFormat::iter() {
+            match License::decode_format(&key, format) {
+                Ok(license) => return Ok((license, format)),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                },
+            }
+        }
+
+        // ? INFO: KeyFormat has at least one variant, so some error was recorded
+        Err(first_error.unwrap())
+    }
+
+    fn decode_format(key: &[u8; 25], format: KeyFormat) -> Result<License, KeyError> {
+        match format {
+            KeyFormat::V6 => License::decode::<Aida64>(key),
+        }
+    }
+
+    fn decode<S: KeyScheme>(key: &[u8; 25]) -> Result<License, KeyError> {
+        let result = License::decode_fields::<S>(key);
+        #[cfg(feature = "tracing")]
+        telemetry::parsed(key, &result);
+
+        result
+    }
+
+    fn decode_fields<S: KeyScheme>(key: &[u8; 25]) -> Result<License, KeyError> {
+        scheme::check_key::<S>(key)?;
+
+        let lossy = LossyLicense::decode::<S>(key);
+
+        Ok(License {
+            edition: KeyEdition::try_from(lossy.edition)?,
+            seats: lossy.seats,
+            purchase_date: lossy.purchase_date.ok_or(KeyError::InvalidDate)?,
+            expiry: lossy.expiry,
+            maintenance_expiry: lossy.maintenance_expiry,
+            unk1: lossy.components[0],
+            unk2: lossy.components[1],
+            unk3: lossy.components[2],
+            base: scheme::base_pair::<S>(key),
+        })
+    }
+
+    /// Like [`License::from_key`] but accepts lowercase keys, and with `lenient` also replaces
+    /// confusable characters, see [`codec::fold_key`]. Returns the replacements with the license.
+    pub fn from_key_tolerant<T: AsRef<[u8]>>(
+        key: T,
+        lenient: bool,
+    ) -> Result<(License, Vec<Substitution>), KeyError> {
+        let mut key = collect_key(key)?;
+        let substitutions = codec::fold_key(&mut key, lenient);
+
+        Ok((License::from_key_bytes(&key)?, substitutions))
+    }
+
+    /// Reads `reader` line by line and parses every key shaped run of text, lines without one are
+    /// parsed as a whole so they show up as an error. Blank lines are skipped and reading stops at
+    /// the first I/O error.
+    pub fn parse_all<R: BufRead>(
+        reader: R,
+    ) -> impl Iterator<Item = (String, Result<License, KeyError>)> {
+        reader.split(b'\n').map_while(Result::ok).flat_map(|line| {
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+
+            let mut keys: Vec<String> =
+                bulk::find_keys_in_text(line).iter().map(|c| c.text.to_owned()).collect();
+            if keys.is_empty() && !line.is_empty() {
+                keys.push(line.to_owned());
+            }
+
+            keys.into_iter().map(|key| {
+                let license = License::from_key(&key);
+                (key, license)
+            })
+        })
+    }
+
+    /// Decodes whatever it can from a mangled key, only a wrong length is an error. Invalid
+    /// characters decode as the first alphabet character, and every invalid character, a wrong
+    /// checksum and out of range fields are listed in [`LossyLicense::problems`].
+    pub fn from_key_lossy<T: AsRef<[u8]>>(key: T) -> Result<LossyLicense, KeyError> {
+        let key = collect_key(key)?;
+        let mut lossy = LossyLicense::decode::<Aida64>(&key);
+
+        for (index, &b) in key.iter().enumerate() {
+            if digit(b).is_none() {
+                let e = KeyError::InvalidCharacter { index, character: b as char };
+                lossy.problems.push(("key", e));
+            }
+        }
+        if !checksum::verify(key) {
+            let e = KeyError::InvalidChecksum {
+                expected: checksum::compute(&key[0..24]),
+                found: key[24] as u16,
+            };
+            lossy.problems.push(("key", e));
+        }
+        lossy.check_fields();
+
+        Ok(lossy)
+    }
+
+    pub fn generate(&self) -> LicenseKey {
+        self.generate_with_rng(&mut thread_rng())
+    }
+
+    /// Generates a key that only depends on the license and `seed`
+    pub fn generate_with_seed(&self, seed: u64) -> LicenseKey {
+        self.generate_with_rng(&mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Same as [`License::generate`] but draws the base pair from `rng` when none is fixed
+    pub fn generate_with_rng<R: RngCore + ?Sized>(&self, rng: &mut R) -> LicenseKey {
+        let mut enc_key: [u8; 25] = [0; 25];
+        self.generate_into_with_rng(&mut enc_key, rng);
+        LicenseKey::new_unchecked(enc_key)
+    }
+
+    /// Writes a key into `out` instead of returning it, see [`codec::format_into`] for the dashed
+    /// form
+    pub fn generate_into(&self, out: &mut [u8; 25]) {
+        self.generate_into_with_rng(out, &mut thread_rng())
+    }
+
+    /// Same as [`License::generate_into`] but draws the base pair from `rng` when none is fixed
+    pub fn generate_into_with_rng<R: RngCore + ?Sized>(&self, enc_key: &mut [u8; 25], rng: &mut R) {
+        let pair = self.base.unwrap_or_else(|| scheme::random_pair::<Aida64, _>(rng));
+        enc_key[22..24].copy_from_slice(&pair);
+
+        self.encode_into::<Aida64>(enc_key);
+    }
+
+    /// Same as [`License::generate`] for another [`KeyScheme`], a fixed base pair keeps its value
+    /// and is written in the alphabet of `S`
+    pub fn generate_with_scheme<S: KeyScheme>(&self) -> [u8; 25] {
+        let mut key = [0; 25];
+        let pair = self.base.unwrap_or_else(|| scheme::random_pair::<Aida64, _>(&mut thread_rng()));
+        scheme::write_base_pair::<S>(pair, &mut key);
+
+        self.encode_into::<S>(&mut key);
+        key
+    }
+
+    /// Same as [`License::generate`] for keys of `format`
+    pub fn generate_with_format(&self, format: KeyFormat) -> [u8; 25] {
+        match format {
+            KeyFormat::V6 => self.generate_with_scheme::<Aida64>(),
+        }
+    }
+
+    /// Generates the key for an explicit base pair, ignoring any fixed one, the same license and
+    /// pair always give the same key
+    pub fn generate_with_base<T: AsRef<[u8]>>(&self, pair: T) -> Result<LicenseKey, KeyError> {
+        let mut key = [0; 25];
+        key[22..24].copy_from_slice(&parse_base_pair(pair)?);
+
+        self.encode_into::<Aida64>(&mut key);
+        Ok(LicenseKey::new_unchecked(key))
+    }
+
+    /// Encodes every segment and the check character around the base pair already in `enc_key`
+    fn encode_into<S: KeyScheme>(&self, enc_key: &mut [u8; 25]) {
+        let expiry = self.expiry.map(|exp| exp.num_days()).unwrap_or(0) as i32;
+        let values = [
+            self.edition as i32 + 1,
+            self.unk1,
+            self.unk2,
+            self.unk3,
+            self.seats,
+            self.purchase_date.enc(),
+            expiry,
+            self.maintenance_expiry.num_days() as i32,
+        ];
+
+        scheme::mask_into::<S>(values, enc_key);
+        #[cfg(feature = "tracing")]
+        telemetry::generated(enc_key, self);
+    }
+
+    /// Parses `key` and checks every decoded field against this license
+    pub fn verify_key<T: AsRef<[u8]>>(&self, key: T) -> Result<(), KeyError> {
+        let decoded = License::from_key(key)?;
+
+        let fields = [
+            ("edition", decoded.edition == self.edition),
+            ("seats", decoded.seats == self.seats),
+            ("purchase date", decoded.purchase_date == self.purchase_date),
+            ("expiry", decoded.expiry == self.expiry),
+            ("maintenance expiry", decoded.maintenance_expiry == self.maintenance_expiry),
+            ("components", decoded.components() == self.components()),
+        ];
+
+        match fields.iter().find(|(_, matches)| !matches) {
+            Some((field, _)) => Err(KeyError::FieldMismatch { field }),
+            None => Ok(()),
+        }
+    }
+
+    /// Generates `count` distinct dashed keys for this license, see [`License::unique_keys`]
+    pub fn generate_many(&self, count: usize) -> Vec<String> {
+        self.generate_many_with_rng(count, &mut thread_rng())
+    }
+
+    pub fn generate_many_with_rng<R: RngCore + ?Sized>(
+        &self,
+        count: usize,
+        rng: &mut R,
+    ) -> Vec<String> {
+        self.unique_keys_with_rng(rng).take(count).collect()
+    }
+
+    /// Endless stream of distinct dashed keys for this license. The base pair varies first, once
+    /// every base pair is used new components are drawn, a fixed base pair is kept on every key
+    pub fn unique_keys(&self) -> impl Iterator<Item = String> + Send + 'static {
+        self.unique_keys_with_rng(StdRng::from_entropy())
+    }
+
+    /// Same as [`License::unique_keys`] but draws from `rng`
+    pub fn unique_keys_with_rng<R: RngCore>(&self, mut rng: R) -> impl Iterator<Item = String> {
+        let fixed_pair = self.base;
+        let mut license = self.clone();
+        let mut pairs = Vec::new();
+        let mut keys = HashSet::new();
+
+        iter::from_fn(move || loop {
+            if pairs.is_empty() {
+                // ? INFO: the first components are the ones of this license, only later rounds
+                // reroll them
+                if !keys.is_empty() {
+                    license = License { base: fixed_pair, ..license.rerolled(&mut rng) };
+                }
+
+                pairs = match fixed_pair {
+                    Some(pair) => vec![pair],
+                    None => KEY_CHARS
+                        .iter()
+                        .flat_map(|&a| KEY_CHARS.iter().map(move |&b| [a, b]))
+                        .collect(),
+                };
+                pairs.shuffle(&mut rng);
+            }
+
+            let pair = pairs.pop()?;
+            let key = License { base: Some(pair), ..license.clone() }.generate_string(true);
+            if keys.insert(key.clone()) {
+                return Some(key);
+            }
+        })
+    }
+
+    pub fn generate_string(&self, separators: bool) -> String {
+        key_to_string(self.generate().into_bytes(), separators)
+    }
+
+    /// Generates a key written in `style`
+    pub fn format_key(&self, style: KeyStyle) -> String {
+        self.generate().format(style)
+    }
+
+    /// Same as [`License::generate_string`] but draws the base pair from `rng` when none is fixed
+    pub fn generate_string_with_rng<R: RngCore + ?Sized>(
+        &self,
+        separators: bool,
+        rng: &mut R,
+    ) -> String {
+        key_to_string(self.generate_with_rng(rng).into_bytes(), separators)
+    }
+
+    /// Candidate corrections for a key with a single mistyped character, every candidate passes
+    /// the checksum and parses into a license that was valid on its purchase date, so expired keys
+    /// can be repaired too. A key that is valid already is returned as its only candidate
+    pub fn repair_key(key: &str) -> Vec<String> {
+        let key = key.bytes().filter(|b| b.is_ascii_alphanumeric()).collect::<Vec<u8>>();
+        let Ok(key) = <[u8; 25]>::try_from(key) else {
+            return Vec::new();
+        };
+
+        // ? INFO: valid as of the purchase date leaves out whether the license has expired since
+        let is_valid = |key: &[u8; 25]| {
+            checksum::verify(key)
+                && License::from_key(key)
+                    .map(|license| license.is_valid_key_at(license.purchase_date))
+                    .unwrap_or(false)
+        };
+
+        if is_valid(&key) {
+            return vec![key_to_string(key, true)];
+        }
+
+        (0..key.len())
+            .flat_map(|idx| KEY_CHARS.iter().map(move |&c| (idx, c)))
+            .filter(|&(idx, c)| key[idx] != c)
+            .map(|(idx, c)| {
+                let mut candidate = key;
+                candidate[idx] = c;
+                candidate
+            })
+            .filter(is_valid)
+            .map(|candidate| key_to_string(candidate, true))
+            .collect()
+    }
+
+    pub fn is_valid_key(&self) -> bool {
+        self.is_valid_key_at(today())
+    }
+
+    /// Same as [`License::is_valid_key`] but evaluates the expiry against `today` instead of the
+    /// system clock, for reproducible audits
+    pub fn is_valid_key_at<D: IntoNaiveDate>(&self, today: D) -> bool {
+        let today = today.into_naive_date();
+        let mut days_left = 0;
+
+        if purchase_date_range().contains(&self.purchase_date) {
+            let expiry = self.expiry.unwrap_or_else(Duration::zero);
+            days_left = (self.purchase_date + expiry - today).num_days();
+        }
+
+        let valid = (self.expiry.is_none() || days_left > 0)
+            && SEATS_RANGE.contains(&self.seats)
+            && UNK1_RANGE.contains(&self.unk1)
+            && UNK2_RANGE.contains(&self.unk2)
+            && UNK3_RANGE.contains(&self.unk3)
+            && self.maintenance_expiry.num_days() < 3659;
+        #[cfg(feature = "tracing")]
+        if !valid {
+            telemetry::invalid(self);
+        }
+
+        valid
+    }
+}
+
+impl FromStr for License {
+    type Err = KeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        License::from_key(s)
+    }
+}
+
+impl PartialEq for License {
+    fn eq(&self, other: &License) -> bool {
+        self.entitlement() == other.entitlement()
+    }
+}
+
+impl Eq for License {}
+
+impl Hash for License {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // ? INFO: chrono's Duration only implements Hash with some of its features enabled
+        let (edition, seats, purchase_date, expiry, maintenance_expiry) = self.entitlement();
+        let expiry = expiry.map(|expiry| expiry.num_seconds());
+
+        (edition, seats, purchase_date, expiry, maintenance_expiry.num_seconds()).hash(state)
+    }
+}
+
+/// Formats the license as a dashed key, licenses parsed from a key keep their base pair and
+/// format back to the same key, see [`License::summary`] for a readable description
+impl fmt::Display for License {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.generate_string(true))
+    }
+}
+
+impl TryFrom<[u8; 25]> for License {
+    type Error = KeyError;
+
+    fn try_from(value: [u8; 25]) -> Result<Self, Self::Error> {
+        License::from_key(value)
+    }
+}
+
+impl TryFrom<LicenseKey> for License {
+    type Error = KeyError;
+
+    fn try_from(value: LicenseKey) -> Result<Self, Self::Error> {
+        License::from_key_bytes(value.as_bytes())
+    }
+}
+
+impl TryFrom<&[u8; 25]> for License {
+    type Error = KeyError;
+
+    fn try_from(value: &[u8; 25]) -> Result<Self, Self::Error> {
+        License::from_key(value)
+    }
+}
+
+/// Strips separators and checks the length and alphabet of a key
+fn normalize_key<T: AsRef<[u8]>>(key: T) -> Result<[u8; 25], KeyError> {
+    let result = collect_key(&key).and_then(|normalized| {
+        check_alphabet(&normalized)?;
+        Ok(normalized)
+    });
+    #[cfg(feature = "tracing")]
+    if let Err(e) = &result {
+        telemetry::rejected(key.as_ref(), e);
+    }
+
+    result
+}
+
+fn check_range(value: i32, range: RangeInclusive<i32>) -> Result<i32, KeyError> {
+    match range.contains(&value) {
+        true => Ok(value),
+        false => {
+            Err(KeyError::ValueOutOfRange { min: *range.start(), max: *range.end(), found: value })
+        },
+    }
+}
+
+fn key_to_string(key: [u8; 25], separators: bool) -> String {
+    let style = match separators {
+        true => KeyStyle::DASHED,
+        false => KeyStyle::CONTINUOUS,
+    };
+
+    // ? INFO: generated keys only contain the key alphabet, so formatting can't fail
+    codec::format_key(&key, style).unwrap()
+}
+
+pub fn random_base_pair() -> [u8; 2] {
+    scheme::random_pair::<Aida64, _>(&mut thread_rng())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::codec::enc_part;
+    use rand::Rng;
+
+    proptest! {
+        #[test]
+        fn round_trip(license in any::<License>()) {
+            prop_assert!(License::from_key(license.generate()).unwrap().identical(&license));
+            prop_assert!(license.verify_key(license.generate_string(true)).is_ok());
+            let spaced = license.format_key(KeyStyle::DASHED.with_separator(Some(' ')));
+            prop_assert_eq!(License::from_key(spaced).unwrap(), license.clone());
+
+            let (mut key, mut dashed) = ([0; 25], [0; codec::DASHED_KEY_LEN]);
+            license.generate_into(&mut key);
+            prop_assert!(License::from_key_bytes(&key).unwrap().identical(&license));
+            prop_assert_eq!(codec::format_into(&key, &mut dashed).unwrap(), license.to_string());
+        }
+    }
+
+    #[test]
+    fn parse_license() {
+        assert!(
+            License::from_key("  3BH41-94ZD6 4KDT5JD-PUY_TBSN9 ").unwrap().is_valid_key(),
+            "parsed valid license as invalid!"
+        );
+
+        assert!(
+            License::from_key("  3BH41-94ZD6 4KDT5JD-PUY_TBSN2 ").is_err(),
+            "parsed license did not trip the checksum check!"
+        );
+    }
+
+    #[test]
+    fn invalid_date() {
+        let mut key = License::new(KeyEdition::Extreme).generate().into_bytes();
+        let base_val = scheme::decode::<Aida64>(&key[22..24]);
+
+        // ? INFO: 2004-13-01, the month field is 4 bits wide but only 12 months exist
+        let date = (1 << 9) | (13 << 5) | 1;
+        enc_part((base_val & 0xFFFFFF) ^ date ^ 0x7CC1, &mut key[12..16]);
+        key[24] = checksum::check_char(&key[0..24]);
+
+        assert!(matches!(License::from_key(key), Err(KeyError::InvalidDate)));
+    }
+
+    #[test]
+    fn lossy_unknown_edition() {
+        let license = License::new(KeyEdition::Business).with_seats(42);
+        let mut key = license.generate().into_bytes();
+        let base_val = scheme::decode::<Aida64>(&key[22..24]);
+
+        enc_part((base_val & 0xFF) ^ (7 + 1) ^ 0xBF, &mut key[0..2]);
+        key[24] = checksum::check_char(&key[0..24]);
+
+        assert!(matches!(License::from_key(key), Err(KeyError::UnknownEdition { index: Some(7) })));
+
+        let lossy = License::from_key_lossy(key).unwrap();
+        assert_eq!((lossy.edition, lossy.known_edition()), (7, None));
+        assert_eq!(lossy.seats, 42);
+        assert_eq!(lossy.purchase_date, Some(license.purchase_date()));
+        assert_eq!(lossy.components, license.components());
+        assert!(matches!(lossy.problems[..], [("edition", KeyError::UnknownEdition { .. })]));
+    }
+
+    #[test]
+    fn summary() {
+        let purchase = NaiveDate::from_ymd(2024, 3, 1);
+        let license = License::new(KeyEdition::Extreme)
+            .with_seats(5)
+            .with_purchase_date(purchase)
+            .with_maintenance_expiry(Duration::days(3655));
+        assert_eq!(
+            license.summary(),
+            "Extreme edition, 5 seats, purchased 2024-03-01, never expires, maintenance until \
+             2034-03-04"
+        );
+
+        let license = license.with_seats(1).with_license_expiry(Some(Duration::days(30)));
+        assert_eq!(
+            license.summary(),
+            "Extreme edition, 1 seat, purchased 2024-03-01, expires 2024-03-31, maintenance until \
+             2034-03-04"
+        );
+    }
+
+    #[test]
+    fn key_formats() {
+        let license = License::new(KeyEdition::Engineer).with_seats(12);
+
+        for format in KeyFormat::iter() {
+            let key = license.generate_with_format(format);
+            assert_eq!(License::from_key_format(key, format).unwrap(), license);
+        }
+
+        // ? INFO: V6 is the only format, so there is nothing to detect yet
+        let key = license.generate();
+        assert_eq!(KeyFormat::iter().collect::<Vec<_>>(), [KeyFormat::V6]);
+        assert_eq!(
+            License::from_key_any(key).unwrap(),
+            (License::from_key(key).unwrap(), KeyFormat::V6)
+        );
+
+        let mut key = license.generate().into_bytes();
+        key[24] = if key[24] == b'D' { b'Y' } else { b'D' };
+        assert!(matches!(License::from_key_any(key), Err(KeyError::InvalidChecksum { .. })));
+        assert!(matches!(License::from_key_any("ABC"), Err(KeyError::InvalidLength { .. })));
+    }
+
+    #[test]
+    fn lossy_mangled_key() {
+        let license = License::new(KeyEdition::Extreme).with_seats(3);
+        let mut key = license.generate().into_bytes();
+        key[2] = b'0';
+        key[24] = if checksum::check_char(&key[0..24]) == b'D' { b'Y' } else { b'D' };
+
+        let lossy = License::from_key_lossy(key).unwrap();
+        assert_eq!((lossy.known_edition(), lossy.seats), (Some(KeyEdition::Extreme), 3));
+        assert!(matches!(
+            lossy.problems[..2],
+            [
+                ("key", KeyError::InvalidCharacter { index: 2, character: '0' }),
+                ("key", KeyError::InvalidChecksum { .. })
+            ]
+        ));
+
+        let valid = License::from_key_lossy(license.generate()).unwrap();
+        assert!(valid.problems.is_empty());
+        assert!(License::from_key_lossy("ABC").is_err());
+    }
+
+    #[test]
+    fn tolerant() {
+        let key = "3bh41-94zd6-4kdt5-jdpuy-tbsn9";
+        assert!(License::from_key(key).is_err());
+
+        let (license, substitutions) = License::from_key_tolerant(key, false).unwrap();
+        assert_eq!(license, License::from_key(key.to_uppercase()).unwrap());
+        assert!(substitutions.is_empty());
+
+        let license = License::new(KeyEdition::Engineer).with_base_pair(Some(*b"DD")).unwrap();
+        let key = license.generate_string(true).replacen('D', "0", 1);
+        assert!(License::from_key_tolerant(&key, false).is_err());
+
+        let (parsed, substitutions) = License::from_key_tolerant(&key, true).unwrap();
+        assert_eq!(parsed, license);
+        assert_eq!(substitutions.len(), 1);
+    }
+
+    #[test]
+    fn parse_all() {
+        let text = b"3BH41-94ZD6-4KDT5-JDPUY-TBSN9\r\n\n  \nsee 3BH4194ZD64KDT5JDPUYTBSN2 \
+                     and 3BH41-94ZD6-4KDT5-JDPUY-TBSN9\nno key here\n";
+        let parsed: Vec<_> = License::parse_all(&text[..]).collect();
+
+        let keys: Vec<_> = parsed.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "3BH41-94ZD6-4KDT5-JDPUY-TBSN9",
+                "3BH4194ZD64KDT5JDPUYTBSN2",
+                "3BH41-94ZD6-4KDT5-JDPUY-TBSN9",
+                "no key here"
+            ]
+        );
+        assert!(parsed[0].1.is_ok() && parsed[2].1.is_ok());
+        assert!(matches!(parsed[1].1, Err(KeyError::InvalidChecksum { .. })));
+        assert!(matches!(parsed[3].1, Err(KeyError::InvalidLength { .. })));
+    }
+
+    #[test]
+    fn parser_never_panics() {
+        let mut rng = StdRng::seed_from_u64(0xA1DA);
+
+        for _ in 0..20_000 {
+            let mut key: [u8; 25] = [0; 25];
+            key.iter_mut().for_each(|c| *c = KEY_CHARS[rng.gen_range(0..KEY_CHARS.len())]);
+            key[24] = checksum::check_char(&key[0..24]);
+
+            if let Ok(license) = License::from_key(key) {
+                license.is_valid_key();
+                license.generate();
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_character() {
+        assert!(matches!(
+            License::from_key("3BH41-94ZD6-4KDT5-JDPUY-TB0N9"),
+            Err(KeyError::InvalidCharacter { index: 22, character: '0' })
+        ));
+        assert!(matches!(
+            License::from_key("3bH41-94ZD6-4KDT5-JDPUY-TBSN9"),
+            Err(KeyError::InvalidCharacter { index: 1, character: 'b' })
+        ));
+    }
+
+    #[test]
+    fn repair() {
+        let candidates = License::repair_key("3BH41-94ZD6-4KDT5-JDPUY-TBSN2");
+        assert!(candidates.contains(&"3BH41-94ZD6-4KDT5-JDPUY-TBSN9".to_string()));
+
+        let candidates = License::repair_key("3BH41-94ZD6-4KDT5-JDPUY-TBSN9");
+        assert_eq!(candidates, ["3BH41-94ZD6-4KDT5-JDPUY-TBSN9"]);
+
+        assert!(License::repair_key("3BH41").is_empty());
+
+        let expired = License::new(KeyEdition::Business)
+            .with_purchase_date(NaiveDate::from_ymd(2010, 1, 1))
+            .with_license_expiry(Some(Duration::days(30)))
+            .with_base_pair(Some(*b"SN"))
+            .unwrap()
+            .generate()
+            .to_string();
+        // ? INFO: a different check character never passes the checksum
+        let mut typo = expired.clone();
+        typo.replace_range(28..29, if expired.ends_with('X') { "Y" } else { "X" });
+        assert!(License::repair_key(&typo).contains(&expired));
+    }
+
+    #[test]
+    fn from_str_display() {
+        let license = "3BH41-94ZD6-4KDT5-JDPUY-TBSN9".parse::<License>().unwrap();
+        assert_eq!(license.to_string(), "3BH41-94ZD6-4KDT5-JDPUY-TBSN9");
+        assert_eq!(format!("{license}").parse::<License>().unwrap(), license);
+        assert!(format!("{license}").parse::<License>().unwrap().identical(&license));
+        assert_eq!(license.base_pair(), Some(*b"SN"));
+
+        assert!("3BH41-94ZD6-4KDT5-JDPUY-TBSN2".parse::<License>().is_err());
+    }
+
+    #[test]
+    fn from_array() {
+        let key = License::new(KeyEdition::Engineer).with_seats(12).generate().into_bytes();
+
+        assert_eq!(License::try_from(key).unwrap().seats, 12);
+        assert_eq!(License::try_from(&key).unwrap().edition, KeyEdition::Engineer);
+    }
+
+    #[test]
+    fn seat_boundaries() {
+        for seats in [*SEATS_RANGE.start(), *SEATS_RANGE.end()] {
+            let license = License::new(KeyEdition::Business).with_seats(seats);
+            let parsed = License::from_key(license.generate()).unwrap();
+
+            assert_eq!(parsed.seats, seats);
+            assert!(parsed.is_valid_key());
+        }
+
+        assert_eq!(License::new(KeyEdition::Business).with_seats(0).seats, 1);
+        assert_eq!(License::new(KeyEdition::Business).with_seats(798).seats, 797);
+    }
+
+    #[test]
+    fn presets() {
+        let trial = License::trial(KeyEdition::Extreme, 30);
+        assert_eq!(trial.expiry(), Some(Duration::days(30)));
+        assert_eq!(trial.maintenance_expiry(), Duration::days(30));
+        assert_eq!(trial.seats(), 1);
+        assert!(trial.is_valid_key());
+
+        assert_eq!(License::trial(KeyEdition::Extreme, 0).expiry(), Some(Duration::days(1)));
+
+        let site = License::site(KeyEdition::NetworkAudit, 1000);
+        assert_eq!(site.seats(), *SEATS_RANGE.end());
+        assert_eq!(site.expiry(), None);
+        assert_eq!(site.maintenance_expiry(), MaintenancePeriod::max());
+    }
+
+    #[test]
+    fn fixed_components() {
+        let license = License::new(KeyEdition::Extreme)
+            .with_components([123, 4, 56])
+            .unwrap()
+            .with_base_pair(Some(*b"D7"))
+            .unwrap();
+
+        assert_eq!(
+            license.generate(),
+            license.generate(),
+            "fixed base pair generated different keys!"
+        );
+        assert_eq!(&license.generate().as_bytes()[22..24], b"D7");
+        assert_eq!(License::from_key(license.generate()).unwrap().components(), [123, 4, 56]);
+
+        assert!(license.clone().with_components([98, 0, 0]).is_err());
+        assert!(license.with_base_pair(Some(*b"0O")).is_err());
+    }
+
+    #[test]
+    fn decode_parts() {
+        let key = "3BH41-94ZD6-4KDT5-JDPUY-TBSN9";
+        let parts = License::decode_parts(key).unwrap();
+        let edition = ((parts.base & 0xFF) ^ parts.segments[0] ^ 0xBF) - 1;
+
+        assert_eq!(parts.base, parts.segments[8]);
+        assert_eq!(parts.checksum, b'9');
+        assert_eq!(edition, License::from_key(key).unwrap().edition as i32);
+        assert_eq!(parts.unmasked[0] - 1, edition);
+        assert_eq!(parts.unmasked[4], License::from_key(key).unwrap().seats());
+        assert!(License::decode_parts("3BH41-94ZD6-4KDT5-JDPUY-TBSN2").is_ok());
+        assert!(License::decode_parts("3BH41-94ZD6").is_err());
+    }
+
+    #[test]
+    fn watermark() {
+        let license = License::new(KeyEdition::Business)
+            .with_order_id(421)
+            .and_then(|license| license.with_custom_a(7))
+            .and_then(|license| license.with_custom_b(100))
+            .unwrap();
+        let parsed = License::from_key(license.generate()).unwrap();
+
+        assert_eq!((parsed.order_id(), parsed.custom_a(), parsed.custom_b()), (421, 7, 100));
+        assert!(license.clone().with_order_id(990).is_err());
+        assert!(license.with_custom_b(-1).is_err());
+    }
+
+    #[test]
+    fn equality() {
+        use std::collections::HashSet;
+
+        let license = License::new(KeyEdition::Business).with_order_id(421).unwrap();
+        let other = license.clone().with_order_id(422).unwrap();
+        let decoded = License::from_key(license.generate()).unwrap();
+
+        assert_eq!(license, other);
+        assert!(!license.identical(&other));
+        assert!(decoded.identical(&License::from_key(decoded.generate()).unwrap()));
+        assert_eq!(HashSet::from([license.clone(), other, decoded]).len(), 1);
+        assert_ne!(license.clone(), license.with_seats(2));
+    }
+
+    #[test]
+    fn diff() {
+        let issued = License::new(KeyEdition::Extreme)
+            .with_purchase_date(NaiveDate::from_ymd(2020, 1, 1))
+            .with_maintenance_expiry(Duration::days(365));
+        let customer = License::from_key(issued.clone().with_seats(5).generate()).unwrap();
+
+        assert!(issued.diff(&issued.clone().with_order_id(500).unwrap()).is_empty());
+
+        let changes = customer.diff(&issued.clone().with_license_expiry(Some(Duration::days(31))));
+        assert_eq!(
+            changes,
+            [
+                FieldChange::Seats { from: 5, to: 1 },
+                FieldChange::Expiry { from: None, to: Some(NaiveDate::from_ymd(2020, 2, 1)) }
+            ]
+        );
+        assert_eq!(changes[1].to_string(), "expiry: never -> 2020-02-01");
+        assert_eq!(changes[0].field(), "seats");
+    }
+
+    #[test]
+    fn renewal() {
+        let mut license = License::new(KeyEdition::Extreme)
+            .with_purchase_date(NaiveDate::from_ymd(2020, 1, 1))
+            .with_license_expiry(Some(Duration::days(365)))
+            .with_maintenance_expiry(Duration::days(365))
+            .with_order_id(421)
+            .unwrap();
+
+        license.renew(NaiveDate::from_ymd(2021, 1, 1));
+        license.extend_maintenance(Duration::days(30));
+
+        let renewed = License::from_key(license.generate()).unwrap();
+        assert_eq!(renewed.purchase_date(), NaiveDate::from_ymd(2021, 1, 1));
+        assert_eq!(renewed.expiry_date(), Some(NaiveDate::from_ymd(2022, 1, 1)));
+        assert_eq!(renewed.maintenance_expiry(), Duration::days(395));
+        assert_eq!(renewed.order_id(), 421);
+
+        license.extend_maintenance(Duration::days(10_000));
+        assert_eq!(license.maintenance_expiry(), MaintenancePeriod::max());
+    }
+
+    #[test]
+    fn verify_generated() {
+        for edition in KeyEdition::iter() {
+            let license = License::new(edition).with_seats(42);
+            assert!(license.verify_key(license.generate_string(true)).is_ok());
+            assert!(matches!(
+                license.clone().with_seats(41).verify_key(license.generate()),
+                Err(KeyError::FieldMismatch { field: "seats" })
+            ));
+        }
+    }
+
+    #[test]
+    fn valid_at() {
+        let license = License::new(KeyEdition::Extreme)
+            .with_purchase_date(NaiveDate::from_ymd(2020, 1, 1))
+            .with_license_expiry(Some(Duration::days(30)));
+
+        assert!(license.is_valid_key_at(NaiveDate::from_ymd(2020, 1, 10)));
+        assert!(!license.is_valid_key_at(NaiveDate::from_ymd(2021, 1, 10)));
+    }
+
+    #[test]
+    fn remaining() {
+        let license = License::new(KeyEdition::Extreme)
+            .with_purchase_date(NaiveDate::from_ymd(2020, 1, 1))
+            .with_license_expiry(Some(Duration::days(30)))
+            .with_maintenance_expiry(Duration::days(10));
+
+        let day = |day| NaiveDate::from_ymd(2020, 1, day);
+        assert_eq!(license.remaining_days(day(10)), Some(21));
+        assert!(!license.is_expired(day(30)) && license.is_valid_key_at(day(30)));
+        assert!(license.is_expired(day(31)) && !license.is_valid_key_at(day(31)));
+        assert_eq!(license.remaining_days(NaiveDate::from_ymd(2021, 1, 1)), Some(0));
+
+        assert_eq!(license.maintenance_remaining(day(5)), 6);
+        assert!(license.maintenance_active(day(10)));
+        assert!(!license.maintenance_active(day(11)));
+        assert_eq!(license.maintenance_remaining(day(20)), 0);
+
+        let lifetime = License::lifetime(KeyEdition::Business);
+        assert_eq!(lifetime.remaining_days(day(1)), None);
+        assert!(!lifetime.is_expired(NaiveDate::from_ymd(2100, 1, 1)));
+    }
+
+    #[test]
+    fn valid_at_boundaries() {
+        // ? INFO: month-end and year-end purchases, where packed dates used to skew the math
+        for (purchase, days) in [((2024, 1, 31), 2), ((2023, 12, 25), 10), ((2024, 2, 28), 1)] {
+            let purchase = NaiveDate::from_ymd(purchase.0, purchase.1, purchase.2);
+            let license = License::new(KeyEdition::Extreme)
+                .with_purchase_date(purchase)
+                .with_license_expiry(Some(Duration::days(days)));
+            let expiry = purchase + Duration::days(days);
+
+            assert!(license.is_valid_key_at(expiry - Duration::days(1)));
+            assert!(!license.is_valid_key_at(expiry));
+        }
+    }
+
+    #[test]
+    fn generate() {
+        for edition in KeyEdition::iter() {
+            assert!(License::new(edition).is_valid_key(), "generated invalid license!");
+            assert!(
+                License::new(edition).with_license_expiry(Some(Duration::days(50))).is_valid_key(),
+                "generated invalid license when using an expiry!"
+            );
+        }
+    }
+
+    #[test]
+    fn reroll() {
+        let mut rng = StdRng::seed_from_u64(0xA1DA);
+        let license = License::site(KeyEdition::Engineer, 50).with_order_id(421).unwrap();
+
+        let mut rerolled = license.clone();
+        rerolled.reroll_random_fields_with_rng(&mut rng);
+        assert_eq!(rerolled, license);
+        assert!(!rerolled.identical(&license));
+
+        let mut fixed = license.clone();
+        fixed.reroll_base_pair_with_rng(&mut rng);
+        let pair = fixed.base_pair().unwrap();
+        assert_eq!(fixed.generate(), fixed.generate());
+        assert_eq!(fixed.components(), license.components());
+
+        for _ in 0..100 {
+            fixed.reroll_base_pair_with_rng(&mut rng);
+            assert_ne!(fixed.base_pair(), Some(pair));
+            fixed = fixed.with_base_pair(Some(pair)).unwrap();
+        }
+    }
+
+    #[test]
+    fn explicit_base() {
+        let key = *b"3BH4194ZD64KDT5JDPUYTBSN9";
+        let license = License::from_key(key).unwrap();
+        let pair = license.base_pair().unwrap();
+
+        assert_eq!(&pair, b"SN");
+        assert_eq!(license.generate_with_base(pair).unwrap(), key);
+
+        let unfixed = license.clone().with_base_pair(None).unwrap();
+        assert_eq!(unfixed.generate_with_base("SN").unwrap(), key);
+        assert_ne!(unfixed.generate_with_base("DD").unwrap(), key);
+        assert!(unfixed.generate_with_base("S0").is_err());
+    }
+
+    #[test]
+    fn seeded() {
+        let mut rng = StdRng::seed_from_u64(1337);
+        let license = License::new_with_rng(KeyEdition::Extreme, &mut rng);
+        let first = (0..10).map(|_| license.generate_with_rng(&mut rng)).collect::<Vec<_>>();
+
+        let mut rng = StdRng::seed_from_u64(1337);
+        let license = License::new_with_rng(KeyEdition::Extreme, &mut rng);
+        let second = (0..10).map(|_| license.generate_with_rng(&mut rng)).collect::<Vec<_>>();
+
+        assert_eq!(first, second, "same seed generated different keys!");
+        assert_eq!(license.generate_with_seed(7), license.generate_with_seed(7));
+    }
+
+    #[test]
+    fn generate_many() {
+        let license = License::new(KeyEdition::Business);
+
+        let keys = license.generate_many(500);
+        assert_eq!(keys.len(), 500);
+        assert_eq!(keys.iter().collect::<std::collections::HashSet<_>>().len(), 500);
+        assert!(keys.iter().all(|key| license.verify_key(key).is_ok()));
+
+        // ? INFO: past 1156 keys the components differ, the entitlement stays the same
+        let keys = license.generate_many(5000);
+        assert_eq!(keys.iter().collect::<std::collections::HashSet<_>>().len(), 5000);
+        assert!(keys.iter().all(|key| License::from_key(key).unwrap() == license));
+
+        let keys = license.with_base_pair(Some(*b"DD")).unwrap().generate_many(5);
+        assert_eq!(keys.iter().collect::<std::collections::HashSet<_>>().len(), 5);
+        assert!(keys.iter().all(|key| &key[26..28] == "DD"));
+    }
+
+    #[test]
+    fn sweep_dates() {
+        let start = NaiveDate::from_ymd(2023, 12, 15);
+        let end = NaiveDate::from_ymd(2025, 3, 1);
+
+        for license in License::new(KeyEdition::Extreme).sweep_dates(start..=end, DateStep::Day) {
+            let parsed = License::from_key(license.generate()).unwrap();
+            assert_eq!(
+                parsed.purchase_date, license.purchase_date,
+                "purchase date did not round-trip!"
+            );
+        }
+
+        let monthly = License::new(KeyEdition::Extreme)
+            .sweep_dates(NaiveDate::from_ymd(2024, 1, 31)..=end, DateStep::Month)
+            .map(|license| license.purchase_date)
+            .collect::<Vec<_>>();
+        assert_eq!(monthly.len(), 14);
+        assert_eq!(monthly[1], NaiveDate::from_ymd(2024, 2, 29));
+        assert_eq!(monthly[2], NaiveDate::from_ymd(2024, 3, 31));
+    }
+
+    #[test]
+    fn strict_builder() {
+        let license = License::new(KeyEdition::Business)
+            .try_with_seats(797)
+            .and_then(|license| license.try_with_purchase_date(NaiveDate::from_ymd(2004, 1, 1)))
+            .and_then(|license| license.try_with_maintenance_expiry(Duration::days(3658)))
+            .unwrap();
+        assert_eq!(license.seats, 797);
+
+        assert!(matches!(
+            license.clone().try_with_seats(0),
+            Err(KeyError::ValueOutOfRange { found: 0, .. })
+        ));
+        assert!(matches!(
+            license.clone().try_with_purchase_date(NaiveDate::from_ymd(2003, 12, 31)),
+            Err(KeyError::DateOutOfRange { .. })
+        ));
+        assert!(matches!(
+            license.try_with_maintenance_expiry(Duration::zero()),
+            Err(KeyError::ValueOutOfRange { found: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn maintenance_expiry_date() {
+        let license =
+            License::new(KeyEdition::Business).with_purchase_date(NaiveDate::from_ymd(2024, 2, 1));
+
+        let dated =
+            license.clone().with_maintenance_expiry_date(NaiveDate::from_ymd(2025, 2, 1)).unwrap();
+        assert_eq!(dated.maintenance_expiry, Duration::days(366));
+
+        assert!(license
+            .clone()
+            .with_maintenance_expiry_date(NaiveDate::from_ymd(2024, 2, 1))
+            .is_err());
+        assert!(license
+            .clone()
+            .with_maintenance_expiry_date(NaiveDate::from_ymd(2034, 2, 11))
+            .is_err());
+
+        let dated = license.with_expiry_date(NaiveDate::from_ymd(2024, 3, 1)).unwrap();
+        assert_eq!(dated.expiry, Some(Duration::days(29)));
+        assert_eq!(dated.expiry_date(), Some(NaiveDate::from_ymd(2024, 3, 1)));
+        assert_eq!(dated.maintenance_expiry_date(), dated.purchase_date + MaintenancePeriod::max());
+    }
+
+    #[test]
+    fn expiry_helpers() {
+        assert_eq!(Expiry::end_of_month(NaiveDate::from_ymd(2024, 2, 10)), Duration::days(19));
+        assert_eq!(Expiry::end_of_month(NaiveDate::from_ymd(2024, 1, 31)), Duration::days(29));
+        assert_eq!(Expiry::calendar_year_end(NaiveDate::from_ymd(2023, 12, 1)), Duration::days(30));
+        assert_eq!(
+            Expiry::calendar_year_end(NaiveDate::from_ymd(2023, 12, 31)),
+            Duration::days(366)
+        );
+        assert_eq!(
+            MaintenancePeriod::years(NaiveDate::from_ymd(2024, 3, 1), 1),
+            Duration::days(365)
+        );
+        assert_eq!(
+            MaintenancePeriod::years(NaiveDate::from_ymd(2024, 3, 1), 20),
+            MaintenancePeriod::max()
+        );
+    }
+}
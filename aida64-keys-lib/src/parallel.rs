@@ -0,0 +1,41 @@
+use std::collections::HashSet;
+
+use rand::thread_rng;
+use rayon::prelude::*;
+
+use crate::License;
+
+impl License {
+    /// Generates `count` distinct dashed keys across all rayon threads, every key is generated
+    /// from a copy of this license with its own random components and base pair
+    pub fn par_generate_batch(&self, count: usize) -> Vec<String> {
+        let mut keys = HashSet::with_capacity(count);
+
+        while keys.len() < count {
+            let batch = (keys.len()..count)
+                .into_par_iter()
+                .map_init(thread_rng, |rng, _| self.rerolled(rng).generate_string(true))
+                .collect::<Vec<_>>();
+
+            keys.extend(batch);
+        }
+
+        keys.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeyEdition;
+
+    #[test]
+    fn batch() {
+        let license = License::new(KeyEdition::Extreme).with_seats(25);
+        let keys = license.par_generate_batch(5000);
+
+        assert_eq!(keys.len(), 5000);
+        assert_eq!(keys.iter().collect::<HashSet<_>>().len(), 5000);
+        assert!(keys.iter().all(|key| License::from_key(key).unwrap().seats() == 25));
+    }
+}
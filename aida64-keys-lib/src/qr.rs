@@ -0,0 +1,81 @@
+//! QR codes of keys for fulfillment mails and printed certificates, behind the `qr` feature
+
+use qrcode::render::{svg, unicode};
+use qrcode::{Color, QrCode};
+
+use crate::{codec, KeyError, License};
+
+/// QR code of a key in its dashed uppercase form
+pub struct KeyQr(QrCode);
+
+impl KeyQr {
+    /// Encodes `key` after [`codec::canonicalize`], so every accepted spelling gives the same code
+    pub fn new(key: &str) -> Result<KeyQr, KeyError> {
+        let key = codec::canonicalize(key)?;
+
+        // ? INFO: a dashed key is 29 characters, far below what the smallest versions hold
+        Ok(KeyQr(QrCode::new(key).unwrap()))
+    }
+
+    /// Modules per side, without the quiet zone
+    pub fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.0[(x, y)] == Color::Dark
+    }
+
+    /// Standalone SVG document with a quiet zone, at least 200 pixels wide
+    pub fn to_svg(&self) -> String {
+        self.0.render::<svg::Color>().min_dimensions(200, 200).build()
+    }
+
+    /// Two modules per line of half block characters, light on dark so the code scans off a
+    /// terminal with a dark background
+    pub fn to_unicode(&self) -> String {
+        self.0
+            .render::<unicode::Dense1x2>()
+            .dark_color(unicode::Dense1x2::Light)
+            .light_color(unicode::Dense1x2::Dark)
+            .build()
+    }
+}
+
+impl License {
+    /// QR code of a key generated for this license, see [`License::generate_string`]
+    pub fn to_qr(&self) -> Result<KeyQr, KeyError> {
+        KeyQr::new(&self.generate_string(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qr() {
+        let qr = KeyQr::new("3bh41 94zd6 4kdt5 jdpuy tbsn9").unwrap();
+        let svg = qr.to_svg();
+
+        assert_eq!(qr.width(), 25);
+        assert!(qr.is_dark(0, 0));
+        assert!(svg.starts_with("<?xml") && svg.contains("<svg"));
+        assert_eq!(qr.to_unicode().lines().count(), (qr.width() + 8).div_ceil(2));
+        assert!(KeyQr::new("3BH41").is_err());
+    }
+
+    #[test]
+    fn license() {
+        let key = "3BH41-94ZD6-4KDT5-JDPUY-TBSN9";
+        let qr = License::from_key(key).unwrap().to_qr().unwrap();
+        let expected = QrCode::new(key).unwrap();
+
+        assert_eq!(qr.width(), expected.width());
+        for y in 0..qr.width() {
+            for x in 0..qr.width() {
+                assert_eq!(qr.is_dark(x, y), expected[(x, y)] == Color::Dark);
+            }
+        }
+    }
+}
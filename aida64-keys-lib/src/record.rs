@@ -0,0 +1,109 @@
+//! Flat per-key rows shared by the CSV outputs of the CLI and GUI
+
+use chrono::NaiveDate;
+
+use crate::{KeyEdition, License};
+
+/// A key together with the license fields it decodes to, one row of a listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LicenseRecord {
+    pub key: String,
+    pub edition: KeyEdition,
+    pub seats: i32,
+    pub purchase_date: NaiveDate,
+    pub expiry_date: Option<NaiveDate>,
+    pub maintenance_date: NaiveDate,
+}
+
+impl LicenseRecord {
+    pub const CSV_HEADER: &'static str =
+        "key,edition,seats,purchase_date,expiry_date,maintenance_date";
+
+    pub fn new<K: Into<String>>(key: K, license: &License) -> LicenseRecord {
+        LicenseRecord {
+            key: key.into(),
+            edition: license.edition(),
+            seats: license.seats(),
+            purchase_date: license.purchase_date(),
+            expiry_date: license.expiry_date(),
+            maintenance_date: license.maintenance_expiry_date(),
+        }
+    }
+
+    /// The record as a row matching [`LicenseRecord::CSV_HEADER`], without a line break, the
+    /// edition is written as its [`KeyEdition::slug`] like in serde, dates as `YYYY-MM-DD` and a
+    /// missing expiry as an empty column
+    pub fn to_csv_row(&self) -> String {
+        let expiry = self.expiry_date.map(|date| date.to_string()).unwrap_or_default();
+        format!(
+            "{},{},{},{},{expiry},{}",
+            self.key,
+            self.edition.slug(),
+            self.seats,
+            self.purchase_date,
+            self.maintenance_date
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn csv_row() {
+        let license = License::new(KeyEdition::Extreme)
+            .with_seats(3)
+            .with_purchase_date(NaiveDate::from_ymd(2024, 1, 1))
+            .with_maintenance_expiry(Duration::days(366));
+        let record = LicenseRecord::new(license.generate_string(true), &license);
+
+        assert_eq!(record.to_csv_row(), format!("{},extreme,3,2024-01-01,,2025-01-01", record.key));
+        assert_eq!(
+            LicenseRecord::new("", &license.with_license_expiry(Some(Duration::days(31))))
+                .to_csv_row(),
+            ",extreme,3,2024-01-01,2024-02-01,2025-01-01"
+        );
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let date = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap();
+
+        for edition in KeyEdition::all() {
+            let license = License::new(edition)
+                .with_seats(12)
+                .with_purchase_date(NaiveDate::from_ymd(2024, 2, 29))
+                .with_license_expiry(Some(Duration::days(90)));
+            let record = LicenseRecord::new(license.generate_string(true), &license);
+
+            let row = record.to_csv_row();
+            let columns = row.split(',').collect::<Vec<_>>();
+            assert_eq!(columns.len(), LicenseRecord::CSV_HEADER.split(',').count());
+
+            let parsed = LicenseRecord {
+                key: columns[0].to_string(),
+                edition: KeyEdition::try_from(columns[1]).unwrap(),
+                seats: columns[2].parse().unwrap(),
+                purchase_date: date(columns[3]),
+                expiry_date: Some(columns[4]).filter(|value| !value.is_empty()).map(date),
+                maintenance_date: date(columns[5]),
+            };
+            assert_eq!(parsed, record);
+            assert_eq!(License::from_key(&parsed.key).unwrap(), license);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn csv_matches_serde() {
+        let license = License::new(KeyEdition::NetworkAudit);
+        let record = LicenseRecord::new(license.generate_string(true), &license);
+        let json = serde_json::to_value(&record).unwrap();
+
+        assert_eq!(record.to_csv_row().split(',').nth(1), json["edition"].as_str());
+    }
+}
@@ -0,0 +1,258 @@
+//! The constants a key format is made of, [`Aida64`] is the scheme every other API uses
+
+use core::fmt;
+use core::ops::Range;
+
+use rand::{Rng, RngCore};
+use strum_macros::EnumIter;
+
+use crate::checksum::{self, Checksum};
+use crate::{codec, KeyError, KEY_CHARS};
+
+/// Masks a segment is XORed with, the value of the base pair is ANDed with `base` first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentMask {
+    pub base: i32,
+    pub xor: i32,
+}
+
+/// A 25 character key format, 24 characters of segments followed by a check character
+pub trait KeyScheme {
+    /// Characters keys are made of, in digit order
+    const ALPHABET: &'static [u8];
+    /// Key characters of the edition, the three components, seats, purchase date, expiry,
+    /// maintenance expiry and the base pair
+    const SEGMENTS: [Range<usize>; 9];
+    /// Masks of every segment but the base pair, in the order of [`KeyScheme::SEGMENTS`]
+    const MASKS: [SegmentMask; 8];
+    /// Checksum behind the check character
+    const CHECKSUM: Checksum = Checksum::AIDA64;
+
+    /// Digit value of `b`, `None` if it isn't part of the alphabet
+    fn digit(b: u8) -> Option<u8> {
+        Self::ALPHABET.iter().position(|&c| c == b).map(|digit| digit as u8)
+    }
+
+    /// The check character for the 24 characters before it
+    fn check_char(key: &[u8]) -> u8 {
+        let mut enc_checksum = [0; 3];
+        encode::<Self>(Self::CHECKSUM.compute(key) as i32, &mut enc_checksum);
+
+        enc_checksum[1]
+    }
+}
+
+/// The scheme of the current AIDA64 versions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Aida64;
+
+impl KeyScheme for Aida64 {
+    const ALPHABET: &'static [u8] = &KEY_CHARS;
+    const SEGMENTS: [Range<usize>; 9] =
+        [0..2, 2..4, 4..6, 6..8, 8..12, 12..16, 16..19, 19..22, 22..24];
+    const MASKS: [SegmentMask; 8] = [
+        SegmentMask { base: 0xFF, xor: 0xBF },
+        SegmentMask { base: 0xFF, xor: 0xED },
+        SegmentMask { base: 0xFF, xor: 0x77 },
+        SegmentMask { base: 0xFF, xor: 0xDF },
+        SegmentMask { base: 0xFFFFFF, xor: 0x4755 },
+        SegmentMask { base: 0xFFFFFF, xor: 0x7CC1 },
+        SegmentMask { base: 0xFF, xor: 0x3FD },
+        SegmentMask { base: 0xFF, xor: 0x935 },
+    ];
+
+    fn digit(b: u8) -> Option<u8> {
+        codec::digit(b)
+    }
+
+    fn check_char(key: &[u8]) -> u8 {
+        checksum::check_char(key)
+    }
+}
+
+/// Key formats of released AIDA64 versions, selectable on parse and generate
+///
+/// Only the layout of current versions is known, so [`KeyFormat::V6`] is the only variant for now
+/// and [`License::from_key_any`](crate::License::from_key_any) can't tell formats apart yet.
+/// Formats of other versions get a variant once their constants are known.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, EnumIter)]
+#[non_exhaustive]
+pub enum KeyFormat {
+    /// AIDA64 6 and later, laid out by [`Aida64`]
+    #[default]
+    V6,
+}
+
+impl fmt::Display for KeyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyFormat::V6 => write!(f, "v6"),
+        }
+    }
+}
+
+/// Writes `value` into `out` in the alphabet of `S`, most significant first, wrapping values
+/// that don't fit
+pub(crate) fn encode<S: KeyScheme + ?Sized>(value: i32, out: &mut [u8]) {
+    let radix = S::ALPHABET.len() as i32;
+    let mut value = value;
+
+    // ? INFO: euclidean so out of range values can't index out of bounds
+    out.iter_mut().rev().for_each(|x| {
+        *x = S::ALPHABET[value.rem_euclid(radix) as usize];
+        value = value.div_euclid(radix);
+    })
+}
+
+/// Reads characters of the alphabet of `S` back into a value, other characters count as 0
+pub(crate) fn decode<S: KeyScheme + ?Sized>(chars: &[u8]) -> i32 {
+    let radix = S::ALPHABET.len() as i32;
+    chars.iter().fold(0, |result, &c| result * radix + S::digit(c).unwrap_or(0) as i32)
+}
+
+/// Raw value of every segment, the base pair last
+pub(crate) fn segments<S: KeyScheme>(key: &[u8; 25]) -> [i32; 9] {
+    S::SEGMENTS.map(|range| decode::<S>(&key[range]))
+}
+
+/// Segment values with the masks undone, without the base pair
+pub(crate) fn unmask<S: KeyScheme>(key: &[u8; 25]) -> [i32; 8] {
+    let segments = segments::<S>(key);
+    let base = segments[8];
+
+    core::array::from_fn(|idx| (base & S::MASKS[idx].base) ^ segments[idx] ^ S::MASKS[idx].xor)
+}
+
+/// Masks and writes `values` around the base pair already in `key`, followed by the check
+/// character
+pub(crate) fn mask_into<S: KeyScheme>(values: [i32; 8], key: &mut [u8; 25]) {
+    let base = decode::<S>(&key[S::SEGMENTS[8].clone()]);
+
+    for (idx, value) in values.into_iter().enumerate() {
+        let mask = S::MASKS[idx];
+        encode::<S>((base & mask.base) ^ value ^ mask.xor, &mut key[S::SEGMENTS[idx].clone()]);
+    }
+
+    key[24] = S::check_char(&key[0..24]);
+}
+
+/// Fails on the first character outside the alphabet of `S` or on a wrong check character
+pub(crate) fn check_key<S: KeyScheme>(key: &[u8; 25]) -> Result<(), KeyError> {
+    if let Some(index) = key.iter().position(|&b| S::digit(b).is_none()) {
+        return Err(KeyError::InvalidCharacter { index, character: key[index] as char });
+    }
+
+    if S::check_char(&key[0..24]) != key[24] {
+        return Err(KeyError::InvalidChecksum {
+            expected: S::CHECKSUM.compute(&key[0..24]),
+            found: key[24] as u16,
+        });
+    }
+
+    Ok(())
+}
+
+/// Base pair of `key` written in the [`Aida64`] alphabet licenses keep it in, `None` when its value
+/// doesn't fit two characters of that alphabet
+pub(crate) fn base_pair<S: KeyScheme>(key: &[u8; 25]) -> Option<[u8; 2]> {
+    let base = decode::<S>(&key[S::SEGMENTS[8].clone()]);
+    let radix = Aida64::ALPHABET.len() as i32;
+    let mut pair = [0; 2];
+
+    (base < radix * radix).then(|| {
+        encode::<Aida64>(base, &mut pair);
+        pair
+    })
+}
+
+/// Writes a base pair kept in the [`Aida64`] alphabet into the base segment of `key`, in the
+/// alphabet of `S`
+pub(crate) fn write_base_pair<S: KeyScheme>(pair: [u8; 2], key: &mut [u8; 25]) {
+    encode::<S>(decode::<Aida64>(&pair), &mut key[S::SEGMENTS[8].clone()]);
+}
+
+pub(crate) fn random_pair<S: KeyScheme, R: RngCore + ?Sized>(rng: &mut R) -> [u8; 2] {
+    [0; 2].map(|_| S::ALPHABET[rng.gen_range(0..S::ALPHABET.len())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KeyEdition, License};
+
+    /// [`Aida64`] without its table lookups, to check the default methods against them
+    struct Plain;
+
+    impl KeyScheme for Plain {
+        const ALPHABET: &'static [u8] = Aida64::ALPHABET;
+        const SEGMENTS: [Range<usize>; 9] = Aida64::SEGMENTS;
+        const MASKS: [SegmentMask; 8] = Aida64::MASKS;
+    }
+
+    /// Same layout with the alphabet reversed, other masks and another checksum polynomial
+    struct Reversed;
+
+    impl KeyScheme for Reversed {
+        const ALPHABET: &'static [u8] = b"E7ZV8MP2SGA5N9TJKI6BQLXCWHR3FU41YD";
+        const SEGMENTS: [Range<usize>; 9] = Aida64::SEGMENTS;
+        const MASKS: [SegmentMask; 8] = [SegmentMask { base: 0xFF, xor: 0x55 }; 8];
+        const CHECKSUM: Checksum = Checksum { polynomial: 0x1021, ..Checksum::AIDA64 };
+    }
+
+    /// Same alphabet and masks with the base pair moved to the front
+    struct BaseFirst;
+
+    impl KeyScheme for BaseFirst {
+        const ALPHABET: &'static [u8] = Aida64::ALPHABET;
+        const SEGMENTS: [Range<usize>; 9] =
+            [2..4, 4..6, 6..8, 8..10, 10..14, 14..18, 18..21, 21..24, 0..2];
+        const MASKS: [SegmentMask; 8] = Aida64::MASKS;
+    }
+
+    #[test]
+    fn default_scheme() {
+        let key = *b"3BH4194ZD64KDT5JDPUYTBSN9";
+        let mut encoded = [0; 25];
+        encoded[22..24].copy_from_slice(&key[22..24]);
+        mask_into::<Aida64>(unmask::<Aida64>(&key), &mut encoded);
+
+        assert_eq!(encoded, key);
+        assert!(check_key::<Aida64>(&key).is_ok());
+        assert_eq!(Plain::check_char(&key[0..24]), key[24]);
+        assert!((0..=u8::MAX).all(|b| Plain::digit(b) == Aida64::digit(b)));
+    }
+
+    #[test]
+    fn other_scheme() {
+        let license = License::site(KeyEdition::Engineer, 20);
+        let key = license.generate_with_scheme::<Reversed>();
+
+        assert!(License::from_key(key).map_or(true, |parsed| parsed != license));
+        assert_eq!(License::from_key_with_scheme::<Reversed, _>(key).unwrap(), license);
+    }
+
+    #[test]
+    fn other_base_pair() {
+        let license = License::site(KeyEdition::Extreme, 5).with_base_pair(Some(*b"SN")).unwrap();
+
+        let key = license.generate_with_scheme::<BaseFirst>();
+        assert_eq!(key[0..2], *b"SN");
+        let parsed = License::from_key_with_scheme::<BaseFirst, _>(key).unwrap();
+        assert!(parsed.identical(&license));
+        assert_eq!(parsed.generate_with_scheme::<BaseFirst>(), key);
+
+        let key = license.generate_with_scheme::<Reversed>();
+        let reversed = |b| Reversed::ALPHABET[Aida64::digit(b).unwrap() as usize];
+        assert_eq!(key[22..24], [reversed(b'S'), reversed(b'N')]);
+        let parsed = License::from_key_with_scheme::<Reversed, _>(key).unwrap();
+        assert_eq!(parsed.base_pair(), Some(*b"SN"));
+        assert!(parsed.identical(&license));
+        assert_eq!(parsed.generate_with_scheme::<Reversed>(), key);
+
+        for _ in 0..100 {
+            let key = License::new(KeyEdition::Business).generate_with_scheme::<BaseFirst>();
+            let parsed = License::from_key_with_scheme::<BaseFirst, _>(key).unwrap();
+            assert_eq!(parsed.base_pair(), Some([key[0], key[1]]));
+        }
+    }
+}
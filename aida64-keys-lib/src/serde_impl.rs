@@ -0,0 +1,131 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{KeyEdition, KeyError, License, LicenseKey};
+
+#[derive(Serialize, Deserialize)]
+struct LicenseRepr {
+    edition: KeyEdition,
+    seats: i32,
+    purchase_date: NaiveDate,
+    expiry_days: Option<i64>,
+    maintenance_days: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    components: Option<[i32; 3]>,
+}
+
+impl Serialize for License {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        LicenseRepr {
+            edition: self.edition(),
+            seats: self.seats(),
+            purchase_date: self.purchase_date(),
+            expiry_days: self.expiry().map(|expiry| expiry.num_days()),
+            maintenance_days: self.maintenance_expiry().num_days(),
+            components: Some(self.components()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for License {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = LicenseRepr::deserialize(deserializer)?;
+
+        let field = |field: &'static str| {
+            move |e: KeyError| serde::de::Error::custom(format!("{field}: {e}"))
+        };
+
+        // ? INFO: out of range fields are rejected instead of clamped like the with_* builders do
+        let license = License::new(repr.edition)
+            .try_with_seats(repr.seats)
+            .map_err(field("seats"))?
+            .try_with_purchase_date(repr.purchase_date)
+            .map_err(field("purchase_date"))?
+            .try_with_license_expiry(repr.expiry_days.map(Duration::days))
+            .map_err(field("expiry_days"))?
+            .try_with_maintenance_expiry(Duration::days(repr.maintenance_days))
+            .map_err(field("maintenance_days"))?;
+
+        match repr.components {
+            Some(components) => {
+                license.with_components(components).map_err(serde::de::Error::custom)
+            },
+            None => Ok(license),
+        }
+    }
+}
+
+/// Dashed, keys are validated when deserialized
+impl Serialize for LicenseKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LicenseKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        LicenseKey::new(key).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let license = License::new(KeyEdition::NetworkAudit)
+            .with_seats(5)
+            .with_purchase_date(NaiveDate::from_ymd(2024, 3, 1))
+            .with_license_expiry(Some(Duration::days(365)));
+
+        let json = serde_json::to_string(&license).unwrap();
+        assert!(json.contains(r#""edition":"network""#));
+        assert!(json.contains(r#""purchase_date":"2024-03-01""#));
+
+        let parsed: License = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.edition(), license.edition());
+        assert_eq!(parsed.seats(), license.seats());
+        assert_eq!(parsed.purchase_date(), license.purchase_date());
+        assert_eq!(parsed.expiry(), license.expiry());
+        assert_eq!(parsed.components(), license.components());
+    }
+
+    #[test]
+    fn missing_components() {
+        let json = r#"{"edition":"extreme","seats":1,"purchase_date":"2024-03-01","expiry_days":null,"maintenance_days":730}"#;
+        let parsed: License = serde_json::from_str(json).unwrap();
+
+        assert!(parsed.is_valid_key());
+        assert_eq!(parsed.maintenance_expiry(), Duration::days(730));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let parse = |seats: i32, purchase_date: &str, expiry: &str, maintenance: i64| {
+            let json = format!(
+                r#"{{"edition":"extreme","seats":{seats},"purchase_date":"{purchase_date}","expiry_days":{expiry},"maintenance_days":{maintenance}}}"#
+            );
+            serde_json::from_str::<License>(&json).map_err(|e| e.to_string())
+        };
+
+        assert!(parse(1, "2024-03-01", "null", 730).is_ok());
+        assert!(parse(0, "2024-03-01", "null", 730).unwrap_err().starts_with("seats: "));
+        assert!(parse(1, "1999-01-01", "null", 730).unwrap_err().starts_with("purchase_date: "));
+        assert!(parse(1, "2024-03-01", "0", 730).unwrap_err().starts_with("expiry_days: "));
+        assert!(parse(1, "2024-03-01", "null", 99999)
+            .unwrap_err()
+            .starts_with("maintenance_days: "));
+    }
+
+    #[test]
+    fn license_key() {
+        let json = r#""3BH41-94ZD6-4KDT5-JDPUY-TBSN9""#;
+        let key: LicenseKey = serde_json::from_str(json).unwrap();
+
+        assert_eq!(serde_json::to_string(&key).unwrap(), json);
+        assert!(serde_json::from_str::<LicenseKey>(r#""3BH41-94ZD6-4KDT5-JDPUY-TBSN2""#).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+//! [proptest] strategies for property testing code built on this crate, enabled by the
+//! `test-strategies` feature
+
+use chrono::Duration;
+use proptest::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::codec::ALPHABET;
+use crate::license::purchase_date_range;
+use crate::{
+    KeyEdition, License, MaintenancePeriod, SEATS_RANGE, UNK1_RANGE, UNK2_RANGE, UNK3_RANGE,
+};
+
+pub fn edition() -> impl Strategy<Value = KeyEdition> {
+    prop::sample::select(KeyEdition::iter().collect::<Vec<_>>())
+}
+
+/// Any license a key can encode, with a fixed base pair so it always generates the same key.
+/// The expiry may lie in the past, see [`valid_license`] for licenses that are valid today
+pub fn license() -> impl Strategy<Value = License> {
+    let purchase_days = (*purchase_date_range().end() - *purchase_date_range().start()).num_days();
+    (
+        edition(),
+        SEATS_RANGE,
+        0..=purchase_days,
+        prop::option::of(1..=MaintenancePeriod::max().num_days()),
+        1..=MaintenancePeriod::max().num_days(),
+        (UNK1_RANGE, UNK2_RANGE, UNK3_RANGE),
+        prop::array::uniform2(prop::sample::select(ALPHABET.to_vec())),
+    )
+        .prop_map(|(edition, seats, purchase, expiry, maintenance, components, base)| {
+            License::new(edition)
+                .with_seats(seats)
+                .with_purchase_date(*purchase_date_range().start() + Duration::days(purchase))
+                .with_license_expiry(expiry.map(Duration::days))
+                .with_maintenance_expiry(Duration::days(maintenance))
+                .with_components([components.0, components.1, components.2])
+                .unwrap()
+                .with_base_pair(Some(base))
+                .unwrap()
+        })
+}
+
+/// Licenses for which [`License::is_valid_key`] holds when the strategy runs
+pub fn valid_license() -> impl Strategy<Value = License> {
+    license().prop_filter("license is expired", License::is_valid_key)
+}
+
+impl Arbitrary for KeyEdition {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<KeyEdition>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        edition().boxed()
+    }
+}
+
+/// Same as [`license`]
+impl Arbitrary for License {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<License>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        license().boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn valid(license in valid_license()) {
+            prop_assert!(license.is_valid_key());
+            prop_assert!(License::from_key(license.generate()).unwrap().identical(&license));
+        }
+    }
+}
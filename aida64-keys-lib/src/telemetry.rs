@@ -0,0 +1,88 @@
+//! [`tracing`] events for parsing, generating and validating keys
+//!
+//! Keys are logged redacted, only their last four characters are kept. [`log_full_keys`] opts in
+//! to an additional trace level event carrying the whole key, for debugging rejected keys.
+
+use core::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{KeyError, License};
+
+static FULL_KEYS: AtomicBool = AtomicBool::new(false);
+
+/// Opts in to logging whole keys at the trace level, off by default
+pub fn log_full_keys(enabled: bool) {
+    FULL_KEYS.store(enabled, Ordering::Relaxed);
+}
+
+/// A key as it shows up in events, every character but the last four replaced by `*`
+pub(crate) struct Redacted<'a>(pub &'a [u8]);
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let shown = self.0.len().saturating_sub(4);
+
+        self.0.iter().enumerate().try_for_each(|(idx, &b)| {
+            match idx < shown || !b.is_ascii_graphic() {
+                true => write!(f, "*"),
+                false => write!(f, "{}", b as char),
+            }
+        })
+    }
+}
+
+fn full_key(key: &[u8], message: &str) {
+    if FULL_KEYS.load(Ordering::Relaxed) {
+        tracing::trace!(key = %String::from_utf8_lossy(key), "{message}");
+    }
+}
+
+pub(crate) fn rejected(key: &[u8], e: &KeyError) {
+    tracing::debug!(key = %Redacted(key), error = %e, "rejected key");
+    full_key(key, "rejected key");
+}
+
+pub(crate) fn parsed(key: &[u8], result: &Result<License, KeyError>) {
+    match result {
+        Ok(license) => tracing::trace!(
+            key = %Redacted(key),
+            edition = %license.edition(),
+            seats = license.seats(),
+            "parsed key"
+        ),
+        Err(e) => rejected(key, e),
+    }
+}
+
+pub(crate) fn generated(key: &[u8], license: &License) {
+    tracing::debug!(
+        key = %Redacted(key),
+        edition = %license.edition(),
+        seats = license.seats(),
+        "generated key"
+    );
+    full_key(key, "generated key");
+}
+
+pub(crate) fn invalid(license: &License) {
+    tracing::debug!(
+        edition = %license.edition(),
+        seats = license.seats(),
+        purchase_date = %license.purchase_date(),
+        expiry = ?license.expiry_date(),
+        components = ?license.components(),
+        "license is not valid"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacted() {
+        assert_eq!(Redacted(b"3BH4194ZD64KDT5JDPUYTBSN9").to_string(), "*********************BSN9");
+        assert_eq!(Redacted(b"ABC").to_string(), "ABC");
+        assert_eq!(Redacted(b"ABCDE\n").to_string(), "**CDE*");
+    }
+}
@@ -0,0 +1,55 @@
+//! JavaScript bindings, see the README for building them with `cargo rustc --crate-type cdylib`
+
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{KeyEdition, License, LicenseRecord};
+
+#[derive(Serialize)]
+struct ParsedKey {
+    #[serde(flatten)]
+    record: LicenseRecord,
+    valid: bool,
+}
+
+/// Generates a dashed key, omitted arguments default to the same values as [`License::new`] and
+/// out of range values throw instead of being clamped
+#[wasm_bindgen]
+pub fn generate(
+    edition: &str,
+    seats: Option<i32>,
+    purchase_date: Option<String>,
+    expiry_days: Option<i32>,
+    maintenance_days: Option<i32>,
+) -> Result<String, JsError> {
+    let mut license = License::new(KeyEdition::try_from(edition)?);
+
+    if let Some(seats) = seats {
+        license = license.try_with_seats(seats)?;
+    }
+    if let Some(date) = purchase_date {
+        license = license.try_with_purchase_date(date.parse::<NaiveDate>()?)?;
+    }
+    if let Some(days) = expiry_days {
+        let date = license.purchase_date() + Duration::days(days.into());
+        license = license.with_expiry_date(date)?;
+    }
+    if let Some(days) = maintenance_days {
+        license = license.try_with_maintenance_expiry(Duration::days(days.into()))?;
+    }
+
+    Ok(license.generate_string(true))
+}
+
+/// Decodes a key into a plain object with the [`LicenseRecord`] fields and `valid`, throws if
+/// the key can't be decoded at all
+#[wasm_bindgen]
+pub fn parse(key: &str) -> Result<JsValue, JsError> {
+    let license = License::from_key(key)?;
+    let parsed =
+        ParsedKey { record: LicenseRecord::new(key, &license), valid: license.is_valid_key() };
+
+    // ? INFO: flattened structs serialize as maps, which would otherwise become a JS `Map`
+    Ok(parsed.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
@@ -0,0 +1,195 @@
+use std::net::SocketAddr;
+
+use aida64_keys_lib::{bulk, KeyEdition, KeyError, License, LicenseRecord};
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{Duration, NaiveDate};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+/// Upper bound for `count` in a generate request, so a single request can't tie up the server
+const MAX_GENERATE_COUNT: usize = 10_000;
+/// Upper bound for the number of keys in a batch verify request
+const MAX_BATCH_SIZE: usize = 10_000;
+
+#[derive(Parser)]
+#[command(version, about = "Serves the aida64 key generator over HTTP")]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8064")]
+    bind: SocketAddr,
+}
+
+/// Omitted fields fall back to the same defaults as [`License::new`], out of range values are
+/// rejected instead of clamped
+#[derive(Deserialize)]
+struct GenerateRequest {
+    edition: KeyEdition,
+    seats: Option<i32>,
+    purchase_date: Option<NaiveDate>,
+    expiry_days: Option<i64>,
+    maintenance_days: Option<i64>,
+    #[serde(default = "default_count")]
+    count: usize,
+}
+
+fn default_count() -> usize {
+    1
+}
+
+impl GenerateRequest {
+    fn license(&self) -> Result<License, ApiError> {
+        let field = |field: &'static str| move |e| ApiError::Field(field, e);
+
+        let mut license = License::new(self.edition);
+
+        if let Some(seats) = self.seats {
+            license = license.try_with_seats(seats).map_err(field("seats"))?;
+        }
+        if let Some(date) = self.purchase_date {
+            license = license.try_with_purchase_date(date).map_err(field("purchase_date"))?;
+        }
+        if let Some(days) = self.expiry_days {
+            license = license
+                .try_with_license_expiry(Some(Duration::days(days)))
+                .map_err(field("expiry_days"))?;
+        }
+        if let Some(days) = self.maintenance_days {
+            license = license
+                .try_with_maintenance_expiry(Duration::days(days))
+                .map_err(field("maintenance_days"))?;
+        }
+
+        Ok(license)
+    }
+}
+
+#[derive(Deserialize)]
+struct VerifyBatchRequest {
+    keys: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct VerifyResult {
+    key: String,
+    valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<LicenseRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl VerifyResult {
+    fn new(key: String) -> VerifyResult {
+        match License::from_key(&key) {
+            Ok(license) => VerifyResult {
+                valid: license.is_valid_key(),
+                license: Some(LicenseRecord::new(key.as_str(), &license)),
+                error: None,
+                key,
+            },
+            Err(e) => VerifyResult { key, valid: false, license: None, error: Some(e.to_string()) },
+        }
+    }
+}
+
+/// Errors are returned as `{"error": "..."}` with a matching status code
+enum ApiError {
+    Key(KeyError),
+    Field(&'static str, KeyError),
+    Request(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct Body {
+            error: String,
+        }
+
+        let (status, error) = match self {
+            ApiError::Key(e) => (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()),
+            ApiError::Field(field, KeyError::ValueOutOfRange { min, max, found }) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("{field} has to be between {min} and {max}, got {found}"),
+            ),
+            ApiError::Field(field, KeyError::DateOutOfRange { min, max, found }) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("{field} has to be between {min} and {max}, got {found}"),
+            ),
+            ApiError::Field(field, e) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, format!("{field}: {e}"))
+            },
+            ApiError::Request(e) => (StatusCode::BAD_REQUEST, e),
+        };
+
+        (status, Json(Body { error })).into_response()
+    }
+}
+
+/// `POST /licenses`, generates `count` keys for the license in the body
+async fn generate(
+    Json(request): Json<GenerateRequest>,
+) -> Result<Json<Vec<LicenseRecord>>, ApiError> {
+    if !(1..=MAX_GENERATE_COUNT).contains(&request.count) {
+        return Err(ApiError::Request(format!(
+            "count has to be between 1 and {MAX_GENERATE_COUNT}"
+        )));
+    }
+
+    let license = request.license()?;
+    let records = (0..request.count)
+        .map(|_| LicenseRecord::new(license.generate_string(true), &license))
+        .collect();
+
+    Ok(Json(records))
+}
+
+/// `GET /licenses/{key}`, decodes a key and fails if it isn't valid
+async fn inspect(Path(key): Path<String>) -> Result<Json<LicenseRecord>, ApiError> {
+    let license = bulk::validate_key(&key).map_err(ApiError::Key)?;
+    Ok(Json(LicenseRecord::new(key, &license)))
+}
+
+/// `POST /licenses/verify-batch`, reports every key separately instead of failing the request
+async fn verify_batch(
+    Json(request): Json<VerifyBatchRequest>,
+) -> Result<Json<Vec<VerifyResult>>, ApiError> {
+    if request.keys.len() > MAX_BATCH_SIZE {
+        return Err(ApiError::Request(format!(
+            "at most {MAX_BATCH_SIZE} keys can be verified at once"
+        )));
+    }
+
+    Ok(Json(request.keys.into_iter().map(VerifyResult::new).collect()))
+}
+
+fn router() -> Router {
+    Router::new()
+        .route("/licenses", post(generate))
+        .route("/licenses/verify-batch", post(verify_batch))
+        .route("/licenses/:key", get(inspect))
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    let listener = match tokio::net::TcpListener::bind(cli.bind).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("error: failed to bind {}: {e}", cli.bind);
+            std::process::exit(1);
+        },
+    };
+
+    println!("listening on http://{}", cli.bind);
+
+    if let Err(e) = axum::serve(listener, router()).await {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}